@@ -6,3 +6,8 @@ pub const INITIAL_SOL_RESERVE: u64 = 12_330_000_000; // 12.33 SOL in lamports
 pub const TOKEN_RESERVE_PERCENTAGE: f64 = 0.8; // 80% of tokens in bonding curve
 pub const CRR: f64 = 1.314; // Constant Reserve Ratio
 pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000; // Conversion rate for lamports to SOL
+pub const PRICE_PRECISION: u128 = 1_000_000_000; // Scale for spot prices (lamports per token unit)
+pub const BPS_DENOMINATOR: u64 = 10_000; // Basis points in 100%
+pub const POSITION_RENT_BUDGET: u64 = 200_000_000; // Most lamports fronted for the Raydium position accounts, the unused rest is refunded
+pub const AMM_FEE_BPS: u16 = 25; // Swap fee of the built-in constant product pool
+pub const MAX_FEE_SPLIT_RECIPIENTS: usize = 4; // Most wallets protocol fees can be split between
@@ -67,4 +67,88 @@ pub enum PumpFunError {
 
     #[msg("Invalid initial SOL reserve - must be exactly 12.33 SOL")]
     InvalidInitialSolReserve,
+
+    #[msg("Order price condition not met")]
+    OrderNotTriggered,
+
+    #[msg("Order has expired")]
+    OrderExpired,
+
+    #[msg("Order can only be cancelled by its owner while it can still execute")]
+    OrderNotCancellable,
+
+    #[msg("DCA installment is not due yet")]
+    DcaNotDue,
+
+    #[msg("Paying with WSOL requires both the user and curve WSOL accounts")]
+    MissingWsolAccount,
+
+    #[msg("Swap did not return a result")]
+    MissingSwapResult,
+
+    #[msg("Price impact exceeds the allowed maximum")]
+    PriceImpactExceeded,
+
+    #[msg("Pool price is out of range")]
+    InvalidPoolPrice,
+
+    #[msg("Pool price deviates from the curve price beyond tolerance")]
+    PoolPriceMismatch,
+
+    #[msg("Curve migrates to a different target")]
+    WrongMigrationTarget,
+
+    #[msg("Curve missed its graduation deadline and is refunding")]
+    CurveRefunding,
+
+    #[msg("Refunds open only after the graduation deadline passes without completing")]
+    RefundNotAvailable,
+
+    #[msg("Curve has not been migrated yet")]
+    CurveNotMigrated,
+
+    #[msg("SOL vault balance is below the real SOL reserve")]
+    SolVaultMismatch,
+
+    #[msg("Transfer would leave the SOL vault below its rent-exempt minimum")]
+    VaultBelowRentExempt,
+
+    #[msg("WSOL mint must be the native mint")]
+    InvalidWsolMint,
+
+    #[msg("AMM config is not the allowlisted one")]
+    InvalidAmmConfig,
+
+    #[msg("Curve is paused")]
+    CurvePaused,
+
+    #[msg("Circuit breaker tripped for this direction, wait for the window to roll")]
+    CircuitBreakerTripped,
+
+    #[msg("Nothing to claim")]
+    NothingToClaim,
+
+    #[msg("Traders cannot refer their own trades")]
+    SelfReferral,
+
+    #[msg("Fee split recipients or weights are invalid")]
+    InvalidFeeSplit,
+
+    #[msg("Only WSOL buys take the curve WSOL account")]
+    UnexpectedWsolAccount,
+
+    #[msg("Migration preview only covers curves migrating to Raydium CLMM")]
+    PreviewNotSupported,
+
+    #[msg("Observation state is not the pool's Raydium observation account")]
+    InvalidObservationState,
+
+    #[msg("Referrer does not match the one recorded on the order or DCA schedule")]
+    InvalidReferrer,
+
+    #[msg("Migration price tolerance is below the gap the migration fee opens")]
+    ToleranceBelowMigrationFee,
+
+    #[msg("Trade tracker still backs a sell penalty, wait for the penalty window to pass")]
+    SellPenaltyActive,
 }
@@ -0,0 +1,121 @@
+use crate::{
+    errors::PumpFunError,
+    states::AmmPool,
+    utils::{
+        sol_transfer_from_user, sol_transfer_from_vault, token_transfer_user,
+        token_transfer_with_signer, AmmSwapped,
+    },
+};
+
+use anchor_lang::{prelude::*, system_program};
+
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct AmmSwap<'info> {
+    #[account(mut)]
+    user: Signer<'info>,
+
+    #[account(mut, seeds = [AmmPool::SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump = amm_pool.bump)]
+    amm_pool: Box<Account<'info, AmmPool>>,
+
+    token_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut, seeds = [AmmPool::SOL_VAULT_SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump = amm_pool.sol_vault_bump)]
+    amm_sol_vault: SystemAccount<'info>,
+
+    #[account(mut, associated_token::mint = token_mint, associated_token::authority = amm_pool)]
+    amm_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(init_if_needed, payer = user, associated_token::mint = token_mint, associated_token::authority = user)]
+    user_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = token::ID)]
+    token_program: Program<'info, Token>,
+    #[account(address = associated_token::ID)]
+    associated_token_program: Program<'info, AssociatedToken>,
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> AmmSwap<'info> {
+    pub fn process(&mut self, amount: u64, direction: u8, min_out: u64) -> Result<()> {
+        require!(amount > 0 && direction <= 1, PumpFunError::IncorrectValueRange);
+
+        let (amount_out, fee_amount) = self.amm_pool.calculate_amount_out(amount, direction)?;
+        require!(amount_out >= min_out, PumpFunError::InsufficientAmountOut);
+
+        let token_key = self.token_mint.key();
+        let user = self.user.to_account_info();
+        let system_program = self.system_program.to_account_info();
+        let token_program = self.token_program.to_account_info();
+
+        if direction == 0 {
+            //  buy - SOL in, tokens out
+            sol_transfer_from_user(&user, &self.amm_sol_vault.to_account_info(), &system_program, amount)?;
+
+            token_transfer_with_signer(
+                &self.amm_token_account.to_account_info(),
+                &self.amm_pool.to_account_info(),
+                &self.user_token_account.to_account_info(),
+                &token_program,
+                &[&AmmPool::get_signer(&token_key, &self.amm_pool.bump)],
+                amount_out,
+            )?;
+
+            let amm_pool = &mut self.amm_pool;
+            amm_pool.sol_reserve = amm_pool
+                .sol_reserve
+                .checked_add(amount)
+                .ok_or(PumpFunError::InvalidReserves)?;
+            amm_pool.token_reserve = amm_pool
+                .token_reserve
+                .checked_sub(amount_out)
+                .ok_or(PumpFunError::InvalidReserves)?;
+        } else {
+            //  sell - tokens in, SOL out
+            token_transfer_user(
+                &self.user_token_account.to_account_info(),
+                &self.amm_token_account.to_account_info(),
+                &user,
+                &token_program,
+                amount,
+            )?;
+
+            // Never below the vault's rent-exempt minimum funded at migration
+            sol_transfer_from_vault(
+                &self.amm_sol_vault.to_account_info(),
+                &user,
+                &system_program,
+                &[&AmmPool::get_sol_vault_signer(&token_key, &self.amm_pool.sol_vault_bump)],
+                amount_out,
+            )?;
+
+            let amm_pool = &mut self.amm_pool;
+            amm_pool.token_reserve = amm_pool
+                .token_reserve
+                .checked_add(amount)
+                .ok_or(PumpFunError::InvalidReserves)?;
+            amm_pool.sol_reserve = amm_pool
+                .sol_reserve
+                .checked_sub(amount_out)
+                .ok_or(PumpFunError::InvalidReserves)?;
+        }
+
+        emit!(AmmSwapped {
+            token_mint: token_key,
+            user: user.key(),
+            direction,
+            amount_in: amount,
+            amount_out,
+            fee_amount,
+            sol_reserve: self.amm_pool.sol_reserve,
+            token_reserve: self.amm_pool.token_reserve,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{
+    states::{BondingCurve, Config},
+    utils::CurveAudited,
+};
+
+use anchor_lang::prelude::*;
+
+use anchor_spl::token::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct AuditCurve<'info> {
+    auditor: Signer<'info>,
+
+    #[account(seeds = [Config::SEED_PREFIX.as_bytes()], bump)]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(mut, seeds = [BondingCurve::SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump)]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    token_mint: Box<Account<'info, Mint>>,
+
+    #[account(associated_token::mint = token_mint, associated_token::authority = bonding_curve)]
+    curve_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(seeds = [BondingCurve::SOL_VAULT_SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump)]
+    sol_vault: SystemAccount<'info>,
+}
+
+// a - b without overflowing either way
+fn signed_diff(a: u64, b: u64) -> i64 {
+    (a as i128 - b as i128).clamp(i64::MIN as i128, i64::MAX as i128) as i64
+}
+
+impl<'info> AuditCurve<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        let bonding_curve = &mut self.bonding_curve;
+
+        // SOL custody holds the real reserve on top of its rent
+        let sol_balance = self
+            .sol_vault
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(0));
+        let sol_discrepancy = signed_diff(sol_balance, bonding_curve.real_sol_reserve);
+
+        // Curve tokens must cover everything still for sale
+        let token_balance = self.curve_token_account.amount;
+        let token_shortfall = bonding_curve
+            .real_token_reserve
+            .saturating_sub(token_balance);
+
+        // Curve tokens plus circulating tokens is the mint supply, refunds burn their share
+        let supply_discrepancy = signed_diff(
+            self.token_mint
+                .supply
+                .saturating_add(bonding_curve.refunded_tokens),
+            bonding_curve.token_total_supply,
+        );
+
+        // Virtual reserves always include the real ones
+        let virtual_reserves_consistent = bonding_curve.virtual_sol_reserve
+            >= bonding_curve.real_sol_reserve
+            && bonding_curve.virtual_token_reserve >= bonding_curve.real_token_reserve;
+
+        // Only missing funds pause the curve, anyone can donate SOL or burn their own tokens
+        // so surpluses and a smaller supply are only reported
+        let threshold = self.global_config.audit_pause_threshold;
+        let sol_shortfall = bonding_curve.real_sol_reserve.saturating_sub(sol_balance);
+        if threshold > 0 && sol_shortfall.max(token_shortfall) >= threshold {
+            bonding_curve.is_paused = true;
+        }
+
+        emit!(CurveAudited {
+            token_mint: self.token_mint.key(),
+            auditor: self.auditor.key(),
+            sol_balance,
+            real_sol_reserve: bonding_curve.real_sol_reserve,
+            sol_discrepancy,
+            token_balance,
+            real_token_reserve: bonding_curve.real_token_reserve,
+            token_shortfall,
+            supply_discrepancy,
+            virtual_reserves_consistent,
+            paused: bonding_curve.is_paused,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{
+    errors::PumpFunError,
+    states::{BondingCurve, DcaOrder},
+    utils::{sol_transfer_from_program_account, DcaCancelled},
+};
+
+use anchor_lang::prelude::*;
+
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+pub struct CancelDca<'info> {
+    #[account(mut)]
+    canceller: Signer<'info>,
+
+    /// CHECK: Read as a BondingCurve, or as a GraduatedCurve once finalized
+    #[account(
+        owner = crate::ID,
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()],
+        bump
+    )]
+    bonding_curve: UncheckedAccount<'info>,
+
+    token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        has_one = token_mint,
+        seeds = [
+            DcaOrder::SEED_PREFIX.as_bytes(),
+            &token_mint.key().to_bytes(),
+            &owner.key().to_bytes(),
+            &dca_order.dca_id.to_le_bytes(),
+        ],
+        bump = dca_order.bump
+    )]
+    dca_order: Box<Account<'info, DcaOrder>>,
+
+    #[account(mut)]
+    owner: SystemAccount<'info>,
+}
+
+impl<'info> CancelDca<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        let dca_order = &self.dca_order;
+
+        // Owners cancel any time, anyone else only once the curve stops trading
+        let is_owner = self.canceller.key() == dca_order.owner;
+        let is_dead = BondingCurve::stopped_trading(
+            &self.bonding_curve.to_account_info(),
+            Clock::get()?.unix_timestamp,
+        )?;
+        require!(is_owner || is_dead, PumpFunError::OrderNotCancellable);
+
+        let refunded_amount = dca_order
+            .unspent_amount()
+            .ok_or(PumpFunError::MathOverflow)?;
+
+        // Whoever cleans up a dead schedule earns one crank fee
+        if !is_owner {
+            sol_transfer_from_program_account(
+                &dca_order.to_account_info(),
+                &self.canceller.to_account_info(),
+                dca_order.crank_fee,
+            )?;
+        }
+
+        emit!(DcaCancelled {
+            dca_order: dca_order.key(),
+            owner: self.owner.key(),
+            token_mint: self.token_mint.key(),
+            cancelled_by: self.canceller.key(),
+            refunded_amount,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{
+    errors::PumpFunError,
+    states::{BondingCurve, LimitOrder},
+    utils::{release_order_escrow, OrderCancelled},
+};
+
+use anchor_lang::prelude::*;
+
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    #[account(mut)]
+    canceller: Signer<'info>,
+
+    /// CHECK: Read as a BondingCurve, or as a GraduatedCurve once finalized
+    #[account(
+        owner = crate::ID,
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()],
+        bump
+    )]
+    bonding_curve: UncheckedAccount<'info>,
+
+    token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        has_one = token_mint,
+        seeds = [
+            LimitOrder::SEED_PREFIX.as_bytes(),
+            &token_mint.key().to_bytes(),
+            &owner.key().to_bytes(),
+            &limit_order.order_id.to_le_bytes(),
+        ],
+        bump = limit_order.bump
+    )]
+    limit_order: Box<Account<'info, LimitOrder>>,
+
+    #[account(mut, associated_token::mint = token_mint, associated_token::authority = limit_order)]
+    order_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    owner: SystemAccount<'info>,
+
+    #[account(mut, associated_token::mint = token_mint, associated_token::authority = owner)]
+    owner_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = token::ID)]
+    token_program: Program<'info, Token>,
+}
+
+impl<'info> CancelOrder<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        let limit_order = &self.limit_order;
+
+        // Owners cancel any time, anyone else only once the order can never fill
+        let is_owner = self.canceller.key() == limit_order.owner;
+        let now = Clock::get()?.unix_timestamp;
+        let is_dead = limit_order.is_expired(now)
+            || BondingCurve::stopped_trading(&self.bonding_curve.to_account_info(), now)?;
+        require!(is_owner || is_dead, PumpFunError::OrderNotCancellable);
+
+        let token_key = self.token_mint.key();
+        let owner_key = self.owner.key();
+        let order_id = limit_order.order_id.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&LimitOrder::get_signer(
+            &token_key,
+            &owner_key,
+            &order_id,
+            &limit_order.bump,
+        )];
+
+        let order_pda = limit_order.to_account_info();
+        let owner_token_account = self.owner_token_account.to_account_info();
+        let canceller = self.canceller.to_account_info();
+
+        // Return escrowed tokens, escrowed SOL goes back with the order on close.
+        // Whoever cleans up a dead order earns its crank fee
+        release_order_escrow(
+            &order_pda,
+            &self.order_token_account.to_account_info(),
+            &self.owner.to_account_info(),
+            &owner_token_account,
+            &self.token_program.to_account_info(),
+            signer_seeds,
+            (!is_owner).then_some((&canceller, limit_order.crank_fee)),
+        )?;
+
+        emit!(OrderCancelled {
+            order: order_pda.key(),
+            owner: owner_key,
+            token_mint: token_key,
+            cancelled_by: self.canceller.key(),
+            refunded_amount: limit_order.amount,
+        });
+
+        Ok(())
+    }
+}
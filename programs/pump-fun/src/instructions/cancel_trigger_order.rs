@@ -0,0 +1,101 @@
+use crate::{
+    errors::PumpFunError,
+    states::{BondingCurve, TriggerOrder},
+    utils::{release_order_escrow, TriggerOrderCancelled},
+};
+
+use anchor_lang::prelude::*;
+
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct CancelTriggerOrder<'info> {
+    #[account(mut)]
+    canceller: Signer<'info>,
+
+    /// CHECK: Read as a BondingCurve, or as a GraduatedCurve once finalized
+    #[account(
+        owner = crate::ID,
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()],
+        bump
+    )]
+    bonding_curve: UncheckedAccount<'info>,
+
+    token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        has_one = token_mint,
+        seeds = [
+            TriggerOrder::SEED_PREFIX.as_bytes(),
+            &token_mint.key().to_bytes(),
+            &owner.key().to_bytes(),
+            &trigger_order.order_id.to_le_bytes(),
+        ],
+        bump = trigger_order.bump
+    )]
+    trigger_order: Box<Account<'info, TriggerOrder>>,
+
+    #[account(mut, associated_token::mint = token_mint, associated_token::authority = trigger_order)]
+    order_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    owner: SystemAccount<'info>,
+
+    #[account(mut, associated_token::mint = token_mint, associated_token::authority = owner)]
+    owner_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = token::ID)]
+    token_program: Program<'info, Token>,
+}
+
+impl<'info> CancelTriggerOrder<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        let trigger_order = &self.trigger_order;
+
+        // Owners cancel any time, anyone can unwind pending orders once the curve stops trading
+        let is_owner = self.canceller.key() == trigger_order.owner;
+        let is_dead = BondingCurve::stopped_trading(
+            &self.bonding_curve.to_account_info(),
+            Clock::get()?.unix_timestamp,
+        )?;
+        require!(is_owner || is_dead, PumpFunError::OrderNotCancellable);
+
+        let token_key = self.token_mint.key();
+        let owner_key = self.owner.key();
+        let order_id = trigger_order.order_id.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&TriggerOrder::get_signer(
+            &token_key,
+            &owner_key,
+            &order_id,
+            &trigger_order.bump,
+        )];
+
+        let order_pda = trigger_order.to_account_info();
+        let owner_token_account = self.owner_token_account.to_account_info();
+        let canceller = self.canceller.to_account_info();
+
+        // Whoever unwinds a dead order earns its crank fee
+        release_order_escrow(
+            &order_pda,
+            &self.order_token_account.to_account_info(),
+            &self.owner.to_account_info(),
+            &owner_token_account,
+            &self.token_program.to_account_info(),
+            signer_seeds,
+            (!is_owner).then_some((&canceller, trigger_order.crank_fee)),
+        )?;
+
+        emit!(TriggerOrderCancelled {
+            order: order_pda.key(),
+            owner: owner_key,
+            token_mint: token_key,
+            cancelled_by: self.canceller.key(),
+            refunded_amount: trigger_order.amount,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{
+    errors::PumpFunError,
+    states::{BondingCurve, GraduatedCurve},
+    utils::{sol_transfer_from_vault, CreatorFeesClaimed},
+};
+
+use anchor_lang::{prelude::*, system_program};
+
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    #[account(mut)]
+    creator: Signer<'info>,
+
+    /// CHECK: Read as a BondingCurve, or as a GraduatedCurve once finalized
+    #[account(
+        owner = crate::ID,
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()],
+        bump
+    )]
+    bonding_curve: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [BondingCurve::CREATOR_VAULT_SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump)]
+    creator_vault: SystemAccount<'info>,
+
+    token_mint: Box<Account<'info, Mint>>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimCreatorFees<'info> {
+    pub fn process(&mut self, bump_creator_vault: u8) -> Result<()> {
+        // Creator recorded at launch survives finalization on the tombstone
+        let creator = {
+            let data = self.bonding_curve.try_borrow_data()?;
+            match BondingCurve::try_deserialize(&mut &data[..]) {
+                Ok(bonding_curve) => bonding_curve.creator,
+                Err(_) => GraduatedCurve::try_deserialize(&mut &data[..])?.creator,
+            }
+        };
+        require!(
+            creator == self.creator.key(),
+            PumpFunError::UnauthorizedAddress
+        );
+
+        // Everything above the vault's rent has accrued from trades
+        let amount = self
+            .creator_vault
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(0));
+        require!(amount > 0, PumpFunError::NothingToClaim);
+
+        let token_key = self.token_mint.key();
+        let vault_signer_seeds: &[&[&[u8]]] = &[&BondingCurve::get_creator_vault_signer(
+            &token_key,
+            &bump_creator_vault,
+        )];
+
+        sol_transfer_from_vault(
+            &self.creator_vault.to_account_info(),
+            &self.creator.to_account_info(),
+            &self.system_program.to_account_info(),
+            vault_signer_seeds,
+            amount,
+        )?;
+
+        emit!(CreatorFeesClaimed {
+            token_mint: token_key,
+            creator,
+            amount,
+        });
+
+        Ok(())
+    }
+}
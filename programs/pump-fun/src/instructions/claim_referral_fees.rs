@@ -0,0 +1,49 @@
+use crate::{
+    errors::PumpFunError,
+    states::Referrer,
+    utils::{sol_transfer_from_program_account, ReferralFeesClaimed},
+};
+
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ClaimReferralFees<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        seeds = [Referrer::SEED_PREFIX.as_bytes(), &owner.key().to_bytes()],
+        bump = referrer.bump
+    )]
+    referrer: Box<Account<'info, Referrer>>,
+}
+
+impl<'info> ClaimReferralFees<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        // Everything above the account's rent has accrued from referred trades
+        let referrer_info = self.referrer.to_account_info();
+        let amount = referrer_info
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(referrer_info.data_len()));
+        require!(amount > 0, PumpFunError::NothingToClaim);
+
+        sol_transfer_from_program_account(&referrer_info, &self.owner.to_account_info(), amount)?;
+
+        let referrer = &mut self.referrer;
+        referrer.fees_claimed = referrer
+            .fees_claimed
+            .checked_add(amount)
+            .ok_or(PumpFunError::MathOverflow)?;
+
+        emit!(ReferralFeesClaimed {
+            referrer: referrer.key(),
+            owner: referrer.owner,
+            amount,
+            total_claimed: referrer.fees_claimed,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{
+    errors::PumpFunError,
+    states::{Config, TradeTracker},
+    utils::TradeTrackerClosed,
+};
+
+use anchor_lang::prelude::*;
+
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+pub struct CloseTradeTracker<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+
+    #[account(seeds = [Config::SEED_PREFIX.as_bytes()], bump)]
+    global_config: Box<Account<'info, Config>>,
+
+    token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        has_one = token_mint,
+        seeds = [
+            TradeTracker::SEED_PREFIX.as_bytes(),
+            &token_mint.key().to_bytes(),
+            &owner.key().to_bytes(),
+        ],
+        bump = trade_tracker.bump
+    )]
+    trade_tracker: Box<Account<'info, TradeTracker>>,
+}
+
+impl<'info> CloseTradeTracker<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        // Closing wipes the last buy slot, so it has to wait until no sell penalty hangs on it
+        let penalty = self
+            .global_config
+            .sell_penalty(self.trade_tracker.last_buy_slot, Clock::get()?.slot);
+        require!(penalty.penalty_bps == 0, PumpFunError::SellPenaltyActive);
+
+        emit!(TradeTrackerClosed {
+            owner: self.owner.key(),
+            token_mint: self.token_mint.key(),
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,333 @@
+use crate::constants::BPS_DENOMINATOR;
+use crate::errors::PumpFunError;
+use crate::states::{BondingCurve, Config, FeeSplitRecipient, GraduatedCurve, MigrationTarget};
+use crate::utils::{token_transfer_with_signer, PoolFeesCollected};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{spl_token::native_mint, Mint, Token, TokenAccount},
+    token_2022::Token2022,
+};
+use raydium_amm_v3::{self, program::AmmV3};
+
+#[derive(Accounts)]
+pub struct CollectPoolFees<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    /// CHECK: Bonding curve or its finalized tombstone, read in process
+    #[account(
+        owner = crate::ID,
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub bonding_curve: UncheckedAccount<'info>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    #[account(address = native_mint::ID)]
+    pub wsol_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Data-less PDA that owns the migrated position
+    #[account(
+        seeds = [BondingCurve::POSITION_AUTHORITY_SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub position_authority: UncheckedAccount<'info>,
+
+    pub position_nft_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        associated_token::mint = position_nft_mint,
+        associated_token::authority = position_authority,
+    )]
+    pub position_nft_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = wsol_mint,
+        associated_token::authority = position_authority,
+    )]
+    pub position_wsol_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = position_authority,
+    )]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Checked against the pool recorded at migration
+    #[account(mut)]
+    pub pool_state: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by Raydium
+    #[account(mut)]
+    pub protocol_position: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by Raydium
+    #[account(mut)]
+    pub personal_position: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by Raydium
+    #[account(mut)]
+    pub token_vault_0: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by Raydium
+    #[account(mut)]
+    pub token_vault_1: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by Raydium
+    #[account(mut)]
+    pub tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by Raydium
+    #[account(mut)]
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    /// CHECK: Checked against the curve creator in process
+    pub creator: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        associated_token::mint = wsol_mint,
+        associated_token::authority = creator,
+    )]
+    pub creator_wsol_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        associated_token::mint = token_mint,
+        associated_token::authority = creator,
+    )]
+    pub creator_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: This account is verified by through the global config constraint, unused once a fee split is set
+    #[account(constraint = config.fee_recipient == fee_recipient.key() @ PumpFunError::IncorrectFeeRecipient)]
+    pub fee_recipient: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        associated_token::mint = wsol_mint,
+        associated_token::authority = fee_recipient,
+    )]
+    pub fee_recipient_wsol_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        associated_token::mint = token_mint,
+        associated_token::authority = fee_recipient,
+    )]
+    pub fee_recipient_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: SPL memo program
+    #[account(address = spl_memo::id())]
+    pub memo_program: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub token_program_2022: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub raydium_program: Program<'info, AmmV3>,
+}
+
+impl<'info> CollectPoolFees<'info> {
+    pub fn process(ctx: Context<'_, '_, 'info, 'info, CollectPoolFees<'info>>) -> Result<()> {
+        let token_key = ctx.accounts.token_mint.key();
+        let curve = GraduatedCurve::load(token_key, &ctx.accounts.bonding_curve)?;
+
+        require!(
+            curve.migration_target == MigrationTarget::RaydiumClmm,
+            PumpFunError::WrongMigrationTarget
+        );
+        require_keys_eq!(
+            curve.pool,
+            ctx.accounts.pool_state.key(),
+            PumpFunError::InvalidPoolState
+        );
+        require_keys_eq!(
+            curve.lp_mint,
+            ctx.accounts.position_nft_mint.key(),
+            PumpFunError::InvalidPoolTokens
+        );
+        require_keys_eq!(
+            curve.creator,
+            ctx.accounts.creator.key(),
+            PumpFunError::UnauthorizedAddress
+        );
+
+        let sol_before = ctx.accounts.position_wsol_account.amount;
+        let token_before = ctx.accounts.position_token_account.amount;
+
+        // Raydium orders the pool by mint, the project token may come first
+        let token_is_mint_0 = token_key < ctx.accounts.wsol_mint.key();
+        let (recipient_0, recipient_1, vault_0_mint, vault_1_mint) = if token_is_mint_0 {
+            (
+                ctx.accounts.position_token_account.to_account_info(),
+                ctx.accounts.position_wsol_account.to_account_info(),
+                ctx.accounts.token_mint.to_account_info(),
+                ctx.accounts.wsol_mint.to_account_info(),
+            )
+        } else {
+            (
+                ctx.accounts.position_wsol_account.to_account_info(),
+                ctx.accounts.position_token_account.to_account_info(),
+                ctx.accounts.wsol_mint.to_account_info(),
+                ctx.accounts.token_mint.to_account_info(),
+            )
+        };
+
+        let position_bump = ctx.bumps.position_authority;
+        let position_seeds =
+            BondingCurve::get_position_authority_signer(&token_key, &position_bump);
+        let position_signer_seeds = &[&position_seeds[..]];
+
+        // Removing zero liquidity only collects the fees owed to the position
+        let collect_accounts = raydium_amm_v3::cpi::accounts::DecreaseLiquidityV2 {
+            nft_owner: ctx.accounts.position_authority.to_account_info(),
+            nft_account: ctx.accounts.position_nft_account.to_account_info(),
+            personal_position: ctx.accounts.personal_position.to_account_info(),
+            pool_state: ctx.accounts.pool_state.to_account_info(),
+            protocol_position: ctx.accounts.protocol_position.to_account_info(),
+            token_vault_0: ctx.accounts.token_vault_0.to_account_info(),
+            token_vault_1: ctx.accounts.token_vault_1.to_account_info(),
+            tick_array_lower: ctx.accounts.tick_array_lower.to_account_info(),
+            tick_array_upper: ctx.accounts.tick_array_upper.to_account_info(),
+            recipient_token_account_0: recipient_0,
+            recipient_token_account_1: recipient_1,
+            token_program: ctx.accounts.token_program.to_account_info(),
+            token_program_2022: ctx.accounts.token_program_2022.to_account_info(),
+            memo_program: ctx.accounts.memo_program.to_account_info(),
+            vault_0_mint,
+            vault_1_mint,
+        };
+
+        let collect_ctx = CpiContext::new_with_signer(
+            ctx.accounts.raydium_program.to_account_info(),
+            collect_accounts,
+            position_signer_seeds,
+        );
+
+        raydium_amm_v3::cpi::decrease_liquidity_v2(collect_ctx, 0, 0, 0)?;
+
+        ctx.accounts.position_wsol_account.reload()?;
+        ctx.accounts.position_token_account.reload()?;
+
+        let sol_fees = ctx
+            .accounts
+            .position_wsol_account
+            .amount
+            .checked_sub(sol_before)
+            .ok_or(PumpFunError::MathOverflow)?;
+        let token_fees = ctx
+            .accounts
+            .position_token_account
+            .amount
+            .checked_sub(token_before)
+            .ok_or(PumpFunError::MathOverflow)?;
+
+        // Creator takes creator_lp_fee_bps, the protocol gets the rest
+        let creator_bps = ctx.accounts.config.creator_lp_fee_bps as u128;
+        let creator_share = |amount: u64| -> Result<u64> {
+            let share = (amount as u128)
+                .checked_mul(creator_bps)
+                .ok_or(PumpFunError::MathOverflow)?
+                / BPS_DENOMINATOR as u128;
+            Ok(share as u64)
+        };
+        let creator_sol = creator_share(sol_fees)?;
+        let creator_tokens = creator_share(token_fees)?;
+        let protocol_sol = sol_fees - creator_sol;
+        let protocol_tokens = token_fees - creator_tokens;
+
+        // The protocol share follows the fee split. Split wallets pass their WSOL and token
+        // accounts as remaining account pairs in the split's order, the fee recipient's stand
+        // alone otherwise
+        let split = ctx.accounts.config.fee_split_recipients();
+        let mut protocol_accounts = Vec::with_capacity(split.len());
+        if ctx.accounts.config.fee_split.is_empty() {
+            protocol_accounts.push((
+                ctx.accounts.fee_recipient_wsol_account.to_account_info(),
+                ctx.accounts.fee_recipient_token_account.to_account_info(),
+            ));
+        } else {
+            require!(
+                ctx.remaining_accounts.len() >= split.len() * 2,
+                PumpFunError::InvalidFeeSplit
+            );
+            let mints = [ctx.accounts.wsol_mint.key(), token_key];
+            for (share, pair) in split.iter().zip(ctx.remaining_accounts.chunks(2)) {
+                for (info, mint) in pair.iter().zip(mints) {
+                    let account = Account::<TokenAccount>::try_from(info)?;
+                    require!(
+                        account.owner == share.wallet && account.mint == mint,
+                        PumpFunError::InvalidFeeSplit
+                    );
+                }
+                protocol_accounts.push((pair[0].clone(), pair[1].clone()));
+            }
+        }
+        let sol_cuts = FeeSplitRecipient::cuts(&split, protocol_sol)?;
+        let token_cuts = FeeSplitRecipient::cuts(&split, protocol_tokens)?;
+
+        let position_authority = ctx.accounts.position_authority.to_account_info();
+        let position_wsol = ctx.accounts.position_wsol_account.to_account_info();
+        let position_tokens = ctx.accounts.position_token_account.to_account_info();
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let mut payouts = vec![
+            (
+                &position_wsol,
+                ctx.accounts.creator_wsol_account.to_account_info(),
+                creator_sol,
+            ),
+            (
+                &position_tokens,
+                ctx.accounts.creator_token_account.to_account_info(),
+                creator_tokens,
+            ),
+        ];
+        for ((wsol_account, token_account), (sol_cut, token_cut)) in protocol_accounts
+            .into_iter()
+            .zip(sol_cuts.into_iter().zip(token_cuts))
+        {
+            payouts.push((&position_wsol, wsol_account, sol_cut));
+            payouts.push((&position_tokens, token_account, token_cut));
+        }
+        for (from, to, amount) in payouts {
+            if amount > 0 {
+                token_transfer_with_signer(
+                    from,
+                    &position_authority,
+                    &to,
+                    &token_program,
+                    position_signer_seeds,
+                    amount,
+                )?;
+            }
+        }
+
+        emit!(PoolFeesCollected {
+            token_mint: token_key,
+            pool: curve.pool,
+            collected_by: ctx.accounts.cranker.key(),
+            sol_fees,
+            token_fees,
+            creator_sol,
+            creator_tokens,
+            protocol_sol,
+            protocol_tokens,
+        });
+
+        Ok(())
+    }
+}
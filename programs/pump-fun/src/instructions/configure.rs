@@ -1,8 +1,10 @@
 use crate::{
+    constants::BPS_DENOMINATOR,
     errors::PumpFunError,
-    states::{Config, ConfigSettings},
+    states::{Config, ConfigSettings, FeeStats},
+    utils::sol_transfer_from_user,
 };
-use anchor_lang::{prelude::*, system_program};
+use anchor_lang::{prelude::*, solana_program::sysvar, system_program};
 
 #[derive(Accounts)]
 pub struct Configure<'info> {
@@ -18,8 +20,23 @@ pub struct Configure<'info> {
     )]
     global_config: Account<'info, Config>,
 
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [FeeStats::SEED_PREFIX.as_bytes()],
+        space = 8 + FeeStats::LEN,
+        bump
+    )]
+    fee_stats: Box<Account<'info, FeeStats>>,
+
+    #[account(mut, seeds = [FeeStats::VAULT_SEED_PREFIX.as_bytes()], bump)]
+    fee_vault: SystemAccount<'info>,
+
     #[account(address = system_program::ID)]
     system_program: Program<'info, System>,
+
+    #[account(address = sysvar::rent::ID)]
+    rent: Sysvar<'info, Rent>,
 }
 
 impl<'info> Configure<'info> {
@@ -45,6 +62,34 @@ impl<'info> Configure<'info> {
             PumpFunError::UnauthorizedAddress
         );
 
+        require!(
+            new_config.max_price_impact_bps as u64 <= BPS_DENOMINATOR
+                && new_config.migration_price_tolerance_bps as u64 <= BPS_DENOMINATOR
+                && new_config.creator_lp_fee_bps as u64 <= BPS_DENOMINATOR
+                && new_config.breaker_threshold_bps as u64 <= BPS_DENOMINATOR
+                && new_config.sell_penalty_bps as u64 <= BPS_DENOMINATOR
+                && new_config.max_creator_fee_bps as u64 <= BPS_DENOMINATOR
+                && new_config.max_referral_share_bps as u64 <= BPS_DENOMINATOR
+                && new_config.graduation_window >= 0,
+            PumpFunError::IncorrectValueRange
+        );
+
+        // A breaker needs a window to roll, without one a trip would block its direction forever
+        require!(
+            new_config.breaker_threshold_bps == 0 || new_config.breaker_window_slots > 0,
+            PumpFunError::IncorrectValueRange
+        );
+
+        require!(new_config.is_valid_fee_split(), PumpFunError::InvalidFeeSplit);
+
+        // The migration fee comes off the pool's SOL side, so the pool always opens that far below
+        // the final curve price. A tighter tolerance would leave completed curves unmigratable
+        require!(
+            new_config.migration_fee_percentage * 100.0
+                <= new_config.migration_price_tolerance_bps as f64,
+            PumpFunError::ToleranceBelowMigrationFee
+        );
+
         // Copy all fields from ConfigSettings to Config
         self.global_config.authority = new_config.authority;
         self.global_config.fee_recipient = new_config.fee_recipient;
@@ -56,8 +101,41 @@ impl<'info> Configure<'info> {
         self.global_config.buy_fee_percentage = new_config.buy_fee_percentage;
         self.global_config.sell_fee_percentage = new_config.sell_fee_percentage;
         self.global_config.migration_fee_percentage = new_config.migration_fee_percentage;
+        self.global_config.crank_fee = new_config.crank_fee;
+        self.global_config.max_price_impact_bps = new_config.max_price_impact_bps;
+        self.global_config.migration_price_tolerance_bps =
+            new_config.migration_price_tolerance_bps;
+        self.global_config.migration_target = new_config.migration_target;
+        self.global_config.migration_bounty = new_config.migration_bounty;
+        self.global_config.migrator = new_config.migrator;
+        self.global_config.graduation_window = new_config.graduation_window;
+        self.global_config.creator_lp_fee_bps = new_config.creator_lp_fee_bps;
+        self.global_config.clmm_config_index = new_config.clmm_config_index;
+        self.global_config.cpmm_config_index = new_config.cpmm_config_index;
+        self.global_config.audit_pause_threshold = new_config.audit_pause_threshold;
+        self.global_config.breaker_window_slots = new_config.breaker_window_slots;
+        self.global_config.breaker_threshold_bps = new_config.breaker_threshold_bps;
+        self.global_config.sell_penalty_window_slots = new_config.sell_penalty_window_slots;
+        self.global_config.sell_penalty_bps = new_config.sell_penalty_bps;
+        self.global_config.sell_penalty_to_curve = new_config.sell_penalty_to_curve;
+        self.global_config.fee_manager = new_config.fee_manager;
+        self.global_config.launch_fee = new_config.launch_fee;
+        self.global_config.max_creator_fee_bps = new_config.max_creator_fee_bps;
+        self.global_config.max_referral_share_bps = new_config.max_referral_share_bps;
+        self.global_config.fee_split = new_config.fee_split;
+        self.global_config.fee_split_per_trade = new_config.fee_split_per_trade;
         self.global_config.reserved = new_config.reserved;
 
+        // Fund the fee vault with its rent once so any fee can land in it
+        if self.fee_vault.lamports() == 0 {
+            sol_transfer_from_user(
+                &self.admin.to_account_info(),
+                &self.fee_vault.to_account_info(),
+                &self.system_program.to_account_info(),
+                self.rent.minimum_balance(0),
+            )?;
+        }
+
         Ok(())
     }
 }
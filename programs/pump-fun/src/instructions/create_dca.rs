@@ -0,0 +1,117 @@
+use crate::{
+    errors::PumpFunError,
+    states::{BondingCurve, Config, DcaOrder, Referrer},
+    utils::{sol_transfer_from_user, DcaCreated},
+};
+
+use anchor_lang::{prelude::*, system_program};
+
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+#[instruction(dca_id: u64)]
+pub struct CreateDca<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+
+    #[account(seeds = [Config::SEED_PREFIX.as_bytes()], bump)]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(seeds = [BondingCurve::SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump)]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + DcaOrder::LEN,
+        seeds = [
+            DcaOrder::SEED_PREFIX.as_bytes(),
+            &token_mint.key().to_bytes(),
+            &owner.key().to_bytes(),
+            &dca_id.to_le_bytes(),
+        ],
+        bump
+    )]
+    dca_order: Box<Account<'info, DcaOrder>>,
+
+    #[account(init_if_needed, payer = owner, associated_token::mint = token_mint, associated_token::authority = owner)]
+    owner_token_account: Box<Account<'info, TokenAccount>>,
+
+    // Optional frontend referrer, credited when the installments run
+    #[account(
+        seeds = [Referrer::SEED_PREFIX.as_bytes(), &referrer.owner.to_bytes()],
+        bump = referrer.bump,
+        constraint = referrer.owner != owner.key() @ PumpFunError::SelfReferral
+    )]
+    referrer: Option<Box<Account<'info, Referrer>>>,
+
+    #[account(address = token::ID)]
+    token_program: Program<'info, Token>,
+    #[account(address = associated_token::ID)]
+    associated_token_program: Program<'info, AssociatedToken>,
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> CreateDca<'info> {
+    pub fn process(
+        &mut self,
+        dca_id: u64,
+        installment_amount: u64,
+        installments: u64,
+        interval: i64,
+        min_out_per_installment: u64,
+        bump_dca: u8,
+    ) -> Result<()> {
+        require!(
+            self.bonding_curve.is_completed == false,
+            PumpFunError::CurveLimitReached
+        );
+        require!(
+            installment_amount > 0 && installments > 0 && interval > 0,
+            PumpFunError::IncorrectValueRange
+        );
+
+        let dca_order = &mut self.dca_order;
+        dca_order.owner = self.owner.key();
+        dca_order.token_mint = self.token_mint.key();
+        dca_order.dca_id = dca_id;
+        dca_order.installment_amount = installment_amount;
+        dca_order.installments_left = installments;
+        dca_order.interval = interval;
+        // First installment can run right away
+        dca_order.next_execution_at = Clock::get()?.unix_timestamp;
+        dca_order.min_out_per_installment = min_out_per_installment;
+        dca_order.crank_fee = self.global_config.crank_fee;
+        dca_order.referrer = self.referrer.as_ref().map(|referrer| referrer.key());
+        dca_order.bump = bump_dca;
+
+        // Escrow every installment plus its crank fee
+        let escrow_lamports = dca_order
+            .unspent_amount()
+            .ok_or(PumpFunError::MathOverflow)?;
+
+        sol_transfer_from_user(
+            &self.owner.to_account_info(),
+            &dca_order.to_account_info(),
+            &self.system_program.to_account_info(),
+            escrow_lamports,
+        )?;
+
+        emit!(DcaCreated {
+            dca_order: dca_order.key(),
+            owner: self.owner.key(),
+            token_mint: self.token_mint.key(),
+            installment_amount,
+            installments,
+            interval,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{
+    errors::PumpFunError,
+    states::{BondingCurve, Config, CurveTrade, DcaOrder, FeeStats, Referrer, TradeTracker},
+    utils::{sol_transfer_from_program_account, DcaExecuted},
+};
+
+use anchor_lang::{prelude::*, system_program};
+
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct ExecuteDca<'info> {
+    #[account(mut)]
+    cranker: Signer<'info>,
+
+    #[account(seeds = [Config::SEED_PREFIX.as_bytes()], bump)]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(mut, seeds = [BondingCurve::CREATOR_VAULT_SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump)]
+    creator_vault: SystemAccount<'info>,
+
+    #[account(mut, seeds = [FeeStats::SEED_PREFIX.as_bytes()], bump)]
+    fee_stats: Box<Account<'info, FeeStats>>,
+
+    #[account(mut, seeds = [FeeStats::VAULT_SEED_PREFIX.as_bytes()], bump)]
+    fee_vault: SystemAccount<'info>,
+
+    #[account(mut, seeds = [BondingCurve::SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump)]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(mut, seeds = [BondingCurve::SOL_VAULT_SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump)]
+    sol_vault: SystemAccount<'info>,
+
+    token_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut, associated_token::mint = token_mint, associated_token::authority = bonding_curve)]
+    curve_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        has_one = token_mint,
+        seeds = [
+            DcaOrder::SEED_PREFIX.as_bytes(),
+            &token_mint.key().to_bytes(),
+            &owner.key().to_bytes(),
+            &dca_order.dca_id.to_le_bytes(),
+        ],
+        bump = dca_order.bump
+    )]
+    dca_order: Box<Account<'info, DcaOrder>>,
+
+    #[account(mut)]
+    owner: SystemAccount<'info>,
+
+    #[account(mut, associated_token::mint = token_mint, associated_token::authority = owner)]
+    owner_token_account: Box<Account<'info, TokenAccount>>,
+
+    // Owner's last buy slot on this curve, DCA installments count toward the sell penalty like direct trades
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = 8 + TradeTracker::LEN,
+        seeds = [
+            TradeTracker::SEED_PREFIX.as_bytes(),
+            &token_mint.key().to_bytes(),
+            &owner.key().to_bytes(),
+        ],
+        bump
+    )]
+    trade_tracker: Box<Account<'info, TradeTracker>>,
+
+    // Referrer recorded on the schedule, must be passed whenever the schedule has one
+    #[account(mut)]
+    referrer: Option<Box<Account<'info, Referrer>>>,
+
+    #[account(address = token::ID)]
+    token_program: Program<'info, Token>,
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> ExecuteDca<'info> {
+    pub fn process(
+        &mut self,
+        bump_bonding_curve: u8,
+        bump_sol_vault: u8,
+        bump_trade_tracker: u8,
+    ) -> Result<()> {
+        let bonding_curve = &mut self.bonding_curve;
+        require!(
+            bonding_curve.is_completed == false,
+            PumpFunError::CurveLimitReached
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let dca_order = &self.dca_order;
+        require!(dca_order.installments_left > 0, PumpFunError::IncorrectValueRange);
+        require!(now >= dca_order.next_execution_at, PumpFunError::DcaNotDue);
+        // Only the referrer recorded at creation is credited, and it cannot be left out
+        require!(
+            self.referrer.as_ref().map(|referrer| referrer.key()) == dca_order.referrer,
+            PumpFunError::InvalidReferrer
+        );
+
+        let installment_amount = dca_order.installment_amount;
+        let min_out = dca_order.min_out_per_installment;
+        let crank_fee = dca_order.crank_fee;
+
+        let trade_tracker = &mut self.trade_tracker;
+        trade_tracker.track(self.owner.key(), self.token_mint.key(), bump_trade_tracker);
+
+        let dca_pda = self.dca_order.to_account_info();
+        let global_config: &Box<Account<'info, Config>> = &self.global_config;
+
+        let trade = CurveTrade {
+            token_mint: &self.token_mint,
+            user: &dca_pda,
+            user_signer_seeds: &[],
+            user_ata: &self.owner_token_account.to_account_info(),
+            curve_pda: &bonding_curve.to_account_info(),
+            curve_ata: &self.curve_token_account.to_account_info(),
+            sol_vault: &self.sol_vault.to_account_info(),
+            creator_vault: &self.creator_vault.to_account_info(),
+            fee_vault: &self.fee_vault.to_account_info(),
+            fee_stats: &mut self.fee_stats,
+            referrer: self.referrer.as_deref_mut(),
+            system_program: &self.system_program.to_account_info(),
+            token_program: &self.token_program.to_account_info(),
+            curve_bump: bump_bonding_curve,
+            sol_vault_bump: bump_sol_vault,
+            options: global_config.trade_options(None),
+        };
+
+        // One installment of escrowed SOL buys tokens straight into the owner's account
+        bonding_curve.buy(trade, installment_amount, min_out, None)?;
+        trade_tracker.last_buy_slot = Clock::get()?.slot;
+
+        // Pay the cranker out of the escrowed crank fee
+        sol_transfer_from_program_account(&dca_pda, &self.cranker.to_account_info(), crank_fee)?;
+
+        let dca_order = &mut self.dca_order;
+        dca_order.installments_left -= 1;
+        dca_order.next_execution_at = now
+            .checked_add(dca_order.interval)
+            .ok_or(PumpFunError::MathOverflow)?;
+
+        emit!(DcaExecuted {
+            dca_order: dca_pda.key(),
+            owner: self.owner.key(),
+            token_mint: self.token_mint.key(),
+            cranker: self.cranker.key(),
+            sol_amount: installment_amount,
+            installments_left: dca_order.installments_left,
+            crank_fee,
+        });
+
+        // Schedule is done, hand the rent back to the owner
+        if dca_order.installments_left == 0 {
+            dca_order.close(self.owner.to_account_info())?;
+        }
+
+        Ok(())
+    }
+}
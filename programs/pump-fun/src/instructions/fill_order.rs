@@ -0,0 +1,207 @@
+use crate::{
+    errors::PumpFunError,
+    states::{BondingCurve, Config, CurveTrade, FeeStats, LimitOrder, Referrer, TradeTracker},
+    utils::{release_order_escrow, OrderFilled},
+};
+
+use anchor_lang::{prelude::*, system_program};
+
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct FillOrder<'info> {
+    #[account(mut)]
+    cranker: Signer<'info>,
+
+    #[account(seeds = [Config::SEED_PREFIX.as_bytes()], bump)]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(mut, seeds = [BondingCurve::CREATOR_VAULT_SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump)]
+    creator_vault: SystemAccount<'info>,
+
+    #[account(mut, seeds = [FeeStats::SEED_PREFIX.as_bytes()], bump)]
+    fee_stats: Box<Account<'info, FeeStats>>,
+
+    #[account(mut, seeds = [FeeStats::VAULT_SEED_PREFIX.as_bytes()], bump)]
+    fee_vault: SystemAccount<'info>,
+
+    #[account(mut, seeds = [BondingCurve::SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump)]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(mut, seeds = [BondingCurve::SOL_VAULT_SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump)]
+    sol_vault: SystemAccount<'info>,
+
+    token_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut, associated_token::mint = token_mint, associated_token::authority = bonding_curve)]
+    curve_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        has_one = token_mint,
+        seeds = [
+            LimitOrder::SEED_PREFIX.as_bytes(),
+            &token_mint.key().to_bytes(),
+            &owner.key().to_bytes(),
+            &limit_order.order_id.to_le_bytes(),
+        ],
+        bump = limit_order.bump
+    )]
+    limit_order: Box<Account<'info, LimitOrder>>,
+
+    #[account(mut, associated_token::mint = token_mint, associated_token::authority = limit_order)]
+    order_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    owner: SystemAccount<'info>,
+
+    #[account(mut, associated_token::mint = token_mint, associated_token::authority = owner)]
+    owner_token_account: Box<Account<'info, TokenAccount>>,
+
+    // Owner's last buy slot on this curve, filled orders count toward the sell penalty like direct trades
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = 8 + TradeTracker::LEN,
+        seeds = [
+            TradeTracker::SEED_PREFIX.as_bytes(),
+            &token_mint.key().to_bytes(),
+            &owner.key().to_bytes(),
+        ],
+        bump
+    )]
+    trade_tracker: Box<Account<'info, TradeTracker>>,
+
+    // Referrer recorded on the order, must be passed whenever the order has one
+    #[account(mut)]
+    referrer: Option<Box<Account<'info, Referrer>>>,
+
+    #[account(address = token::ID)]
+    token_program: Program<'info, Token>,
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> FillOrder<'info> {
+    pub fn process(
+        &mut self,
+        bump_bonding_curve: u8,
+        bump_sol_vault: u8,
+        bump_trade_tracker: u8,
+    ) -> Result<()> {
+        let bonding_curve = &mut self.bonding_curve;
+        require!(
+            bonding_curve.is_completed == false,
+            PumpFunError::CurveLimitReached
+        );
+
+        let limit_order = &self.limit_order;
+        require!(
+            !limit_order.is_expired(Clock::get()?.unix_timestamp),
+            PumpFunError::OrderExpired
+        );
+
+        let spot_price = bonding_curve.spot_price()?;
+        require!(
+            limit_order.is_triggered(spot_price)?,
+            PumpFunError::OrderNotTriggered
+        );
+        // Only the referrer recorded at placement is credited, and it cannot be left out
+        require!(
+            self.referrer.as_ref().map(|referrer| referrer.key()) == limit_order.referrer,
+            PumpFunError::InvalidReferrer
+        );
+
+        let direction = limit_order.direction;
+        let amount = limit_order.amount;
+        let min_amount_out = limit_order.min_amount_out;
+        let crank_fee = limit_order.crank_fee;
+        let order_bump = limit_order.bump;
+        let order_id = limit_order.order_id.to_le_bytes();
+
+        let token_key = self.token_mint.key();
+        let owner_key = self.owner.key();
+        let signer_seeds: &[&[&[u8]]] = &[&LimitOrder::get_signer(
+            &token_key,
+            &owner_key,
+            &order_id,
+            &order_bump,
+        )];
+
+        let slot = Clock::get()?.slot;
+        let trade_tracker = &mut self.trade_tracker;
+        trade_tracker.track(owner_key, token_key, bump_trade_tracker);
+
+        let order_pda = self.limit_order.to_account_info();
+        let global_config: &Box<Account<'info, Config>> = &self.global_config;
+
+        // Buys land in the owner's account, sells come out of the escrow
+        let user_ata = if direction == 0 {
+            self.owner_token_account.to_account_info()
+        } else {
+            self.order_token_account.to_account_info()
+        };
+
+        let trade = CurveTrade {
+            token_mint: &self.token_mint,
+            user: &order_pda,
+            user_signer_seeds: signer_seeds,
+            user_ata: &user_ata,
+            curve_pda: &bonding_curve.to_account_info(),
+            curve_ata: &self.curve_token_account.to_account_info(),
+            sol_vault: &self.sol_vault.to_account_info(),
+            creator_vault: &self.creator_vault.to_account_info(),
+            fee_vault: &self.fee_vault.to_account_info(),
+            fee_stats: &mut self.fee_stats,
+            referrer: self.referrer.as_deref_mut(),
+            system_program: &self.system_program.to_account_info(),
+            token_program: &self.token_program.to_account_info(),
+            curve_bump: bump_bonding_curve,
+            sol_vault_bump: bump_sol_vault,
+            options: global_config.trade_options(None),
+        };
+
+        if direction == 0 {
+            // Escrowed SOL buys tokens straight into the owner's account
+            bonding_curve.buy(trade, amount, min_amount_out, None)?;
+
+            trade_tracker.last_buy_slot = slot;
+        } else {
+            // Escrowed tokens are sold, proceeds land on the order and go to the owner on close
+            bonding_curve.sell(
+                trade,
+                amount,
+                min_amount_out,
+                global_config.sell_penalty(trade_tracker.last_buy_slot, slot),
+                None,
+            )?;
+        }
+
+        // Pay the cranker out of the escrowed crank fee, return any tokens left in the escrow
+        // and its rent to the owner
+        release_order_escrow(
+            &order_pda,
+            &self.order_token_account.to_account_info(),
+            &self.owner.to_account_info(),
+            &self.owner_token_account.to_account_info(),
+            &self.token_program.to_account_info(),
+            signer_seeds,
+            Some((&self.cranker.to_account_info(), crank_fee)),
+        )?;
+
+        emit!(OrderFilled {
+            order: order_pda.key(),
+            owner: owner_key,
+            token_mint: token_key,
+            cranker: self.cranker.key(),
+            direction,
+            amount,
+            spot_price,
+            crank_fee,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{
+    errors::PumpFunError,
+    states::{BondingCurve, Config, FeeStats, GraduatedCurve},
+    utils::{
+        sol_transfer_from_program_account, sol_transfer_with_signer, token_burn_with_signer,
+        token_close_with_signer, CurveFinalized,
+    },
+};
+
+use anchor_lang::{prelude::*, system_program};
+
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct Finalize<'info> {
+    #[account(seeds = [Config::SEED_PREFIX.as_bytes()], bump)]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(mut, seeds = [FeeStats::SEED_PREFIX.as_bytes()], bump)]
+    fee_stats: Box<Account<'info, FeeStats>>,
+
+    #[account(mut, seeds = [FeeStats::VAULT_SEED_PREFIX.as_bytes()], bump)]
+    fee_vault: SystemAccount<'info>,
+
+    /// CHECK: Deserialized as a BondingCurve in process, then rewritten in place as a GraduatedCurve
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()],
+        bump
+    )]
+    bonding_curve: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [BondingCurve::SOL_VAULT_SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump)]
+    sol_vault: SystemAccount<'info>,
+
+    #[account(mut)]
+    token_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut, associated_token::mint = token_mint, associated_token::authority = bonding_curve)]
+    curve_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = token::ID)]
+    token_program: Program<'info, Token>,
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> Finalize<'info> {
+    pub fn process(&mut self, bump_bonding_curve: u8, bump_sol_vault: u8) -> Result<()> {
+        let curve_pda = self.bonding_curve.to_account_info();
+
+        // Fails once finalized, the discriminator no longer matches
+        let bonding_curve = BondingCurve::try_deserialize(&mut &curve_pda.try_borrow_data()?[..])?;
+        require!(bonding_curve.is_migrated, PumpFunError::CurveNotMigrated);
+
+        let token_key = self.token_mint.key();
+        let signer_seeds: &[&[&[u8]]] =
+            &[&BondingCurve::get_signer(&token_key, &bump_bonding_curve)];
+
+        // Migration moved every curve token, anything left is dust sent in since, burn it so
+        // nobody can hold finalize up with a single token
+        let dust = self.curve_token_account.amount;
+        if dust > 0 {
+            token_burn_with_signer(
+                &self.token_mint.to_account_info(),
+                &self.curve_token_account.to_account_info(),
+                &curve_pda,
+                &self.token_program.to_account_info(),
+                signer_seeds,
+                dust,
+            )?;
+        }
+
+        let fee_vault = self.fee_vault.to_account_info();
+
+        // Close the emptied curve token account and reclaim its rent
+        let ata_rent = self.curve_token_account.to_account_info().lamports();
+        token_close_with_signer(
+            &self.curve_token_account.to_account_info(),
+            &curve_pda,
+            &fee_vault,
+            &self.token_program.to_account_info(),
+            signer_seeds,
+        )?;
+
+        let tombstone = GraduatedCurve::from_bonding_curve(token_key, &bonding_curve);
+
+        // Shrink the curve down to the tombstone
+        let space = 8 + GraduatedCurve::LEN;
+        curve_pda.realloc(space, false)?;
+        tombstone.try_serialize(&mut &mut curve_pda.try_borrow_mut_data()?[..])?;
+
+        // Sweep everything above the tombstone's rent into the fee vault
+        let swept_lamports = curve_pda
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(space));
+        sol_transfer_from_program_account(&curve_pda, &fee_vault, swept_lamports)?;
+
+        // The SOL vault is no longer needed, reclaim its rent reserve on purpose
+        let vault_lamports = self.sol_vault.lamports();
+        let vault_signer_seeds: &[&[&[u8]]] =
+            &[&BondingCurve::get_sol_vault_signer(&token_key, &bump_sol_vault)];
+        sol_transfer_with_signer(
+            &self.sol_vault.to_account_info(),
+            &fee_vault,
+            &self.system_program.to_account_info(),
+            vault_signer_seeds,
+            vault_lamports,
+        )?;
+
+        // Everything reclaimed accrues like any other protocol fee, withdrawals split it
+        let total_swept = swept_lamports
+            .checked_add(ata_rent)
+            .and_then(|total| total.checked_add(vault_lamports))
+            .ok_or(PumpFunError::MathOverflow)?;
+        self.fee_stats.record_finalize_sweep(total_swept)?;
+
+        emit!(CurveFinalized {
+            token_mint: token_key,
+            pool: tombstone.pool,
+            swept_lamports: total_swept,
+        });
+
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use anchor_lang::{prelude::*, system_program, solana_program::sysvar};
 use anchor_spl::{associated_token::{self, AssociatedToken}, metadata::{self, mpl_token_metadata::types::DataV2, Metadata}, token::{self, spl_token::instruction::AuthorityType, Mint, Token, TokenAccount}};
 
-use crate::{constants::TOKEN_DECIMAL, states::{BondingCurve, Config}};
+use crate::{constants::TOKEN_DECIMAL, errors::PumpFunError, states::{BondingCurve, Config, FeeStats}, utils::sol_transfer_from_user};
 
 
 #[derive(Accounts)]
@@ -32,6 +32,26 @@ pub struct Launch<'info>{
     )]
     bonding_curve: Box<Account<'info, BondingCurve>>,
 
+    #[account(
+        mut,
+        seeds = [BondingCurve::SOL_VAULT_SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()],
+        bump
+    )]
+    sol_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::CREATOR_VAULT_SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()],
+        bump
+    )]
+    creator_vault: SystemAccount<'info>,
+
+    #[account(mut, seeds = [FeeStats::SEED_PREFIX.as_bytes()], bump)]
+    fee_stats: Box<Account<'info, FeeStats>>,
+
+    #[account(mut, seeds = [FeeStats::VAULT_SEED_PREFIX.as_bytes()], bump)]
+    fee_vault: SystemAccount<'info>,
+
     #[account(
         init, 
         payer = creator,
@@ -67,11 +87,17 @@ impl<'info> Launch<'info> {
         name: String,
         symbol: String,
         uri: String,
+        creator_fee_bps: u16,
         bump_config: u8,
     ) -> Result<()> {
         let bonding_curve = &mut self.bonding_curve;
         let global_config = &self.global_config;
 
+        require!(
+            creator_fee_bps <= global_config.max_creator_fee_bps,
+            PumpFunError::IncorrectValueRange
+        );
+
         // init bonding curve pda
         bonding_curve.virtual_token_reserve = global_config.initial_virtual_token_reserve;
         bonding_curve.virtual_sol_reserve = global_config.initial_virtual_sol_reserve;
@@ -79,6 +105,42 @@ impl<'info> Launch<'info> {
         bonding_curve.real_sol_reserve = 0;
         bonding_curve.token_total_supply = global_config.total_token_supply;
         bonding_curve.is_completed = false;
+        bonding_curve.migration_target = global_config.migration_target;
+        bonding_curve.creator = self.creator.key();
+        bonding_curve.creator_fee_bps = creator_fee_bps;
+        bonding_curve.graduation_deadline = if global_config.graduation_window > 0 {
+            Clock::get()?
+                .unix_timestamp
+                .checked_add(global_config.graduation_window)
+                .ok_or(PumpFunError::MathOverflow)?
+        } else {
+            0
+        };
+
+        // Fund the SOL vault with its rent so it holds exactly the real SOL reserve on top
+        sol_transfer_from_user(
+            &self.creator.to_account_info(),
+            &self.sol_vault.to_account_info(),
+            &self.system_program.to_account_info(),
+            self.rent.minimum_balance(0),
+        )?;
+
+        // Same for the creator vault, everything above rent is claimable by the creator
+        sol_transfer_from_user(
+            &self.creator.to_account_info(),
+            &self.creator_vault.to_account_info(),
+            &self.system_program.to_account_info(),
+            self.rent.minimum_balance(0),
+        )?;
+
+        // Accrue the launch fee into the protocol fee vault
+        sol_transfer_from_user(
+            &self.creator.to_account_info(),
+            &self.fee_vault.to_account_info(),
+            &self.system_program.to_account_info(),
+            global_config.launch_fee,
+        )?;
+        self.fee_stats.record_launch_fee(global_config.launch_fee)?;
 
         let signer_seeds: &[&[&[u8]]] = &[&[Config::SEED_PREFIX.as_bytes(), &[bump_config]]];
 
@@ -1,19 +1,27 @@
+use crate::constants::POSITION_RENT_BUDGET;
 use crate::errors::PumpFunError;
-use crate::states::{BondingCurve, Config};
-use crate::utils::{sol_transfer_with_signer, token_transfer_with_signer, MigrationCompleted};
+use crate::states::{sorted_mints, BondingCurve, Config, FeeStats, MigrationPlan, MigrationTarget};
+use crate::utils::{full_range_ticks, CurveMigration};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::Metadata,
+    token::{spl_token::native_mint, Mint, Token, TokenAccount},
+};
 use raydium_amm_v3::{
     self,
-    libraries::tick_math,
+    libraries::{liquidity_math, tick_math},
     program::AmmV3,
-    states::{AmmConfig, POOL_SEED, POOL_TICK_ARRAY_BITMAP_SEED, POOL_VAULT_SEED},
+    states::{
+        AmmConfig, TickArrayState, OBSERVATION_SEED, POOL_SEED, POOL_TICK_ARRAY_BITMAP_SEED,
+        POOL_VAULT_SEED,
+    },
 };
 
 #[derive(Accounts)]
 pub struct Migrate<'info> {
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub migrator: Signer<'info>,
 
     #[account(
         mut,
@@ -28,29 +36,30 @@ pub struct Migrate<'info> {
         bump,
         constraint = bonding_curve.is_completed @ PumpFunError::CurveNotCompleted,
         constraint = !bonding_curve.is_migrated @ PumpFunError::AlreadyMigrated,
+        constraint = bonding_curve.real_sol_reserve > 0 @ PumpFunError::InsufficientSolBalance,
+        constraint = bonding_curve.migration_target == MigrationTarget::RaydiumClmm @ PumpFunError::WrongMigrationTarget,
     )]
     pub bonding_curve: Account<'info, BondingCurve>,
 
-    #[account(mut)]
     pub token_mint: Account<'info, Mint>,
 
-    /// CHECK: WSOL mint
+    #[account(address = native_mint::ID @ PumpFunError::InvalidWsolMint)]
     pub wsol_mint: Account<'info, Mint>,
 
     #[account(
         mut,
-        constraint = curve_token_account.owner == bonding_curve.key(),
+        associated_token::mint = token_mint,
+        associated_token::authority = bonding_curve,
         constraint = curve_token_account.amount > 0 @ PumpFunError::InsufficientTokenBalance,
     )]
     pub curve_token_account: Account<'info, TokenAccount>,
 
-    /// CHECK: Native SOL account owned by bonding curve
     #[account(
         mut,
-        constraint = *curve_sol_account.owner == bonding_curve.key(),
-        constraint = curve_sol_account.lamports() > 0 @ PumpFunError::InsufficientSolBalance,
+        seeds = [BondingCurve::SOL_VAULT_SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump,
     )]
-    pub curve_sol_account: AccountInfo<'info>,
+    pub sol_vault: SystemAccount<'info>,
 
     /// CHECK: Pool state account to be created
     #[account(
@@ -58,38 +67,44 @@ pub struct Migrate<'info> {
         seeds = [
             POOL_SEED.as_bytes(),
             amm_config.key().as_ref(),
-            wsol_mint.key().as_ref(),
-            token_mint.key().as_ref(),
+            sorted_mints(&wsol_mint.key(), &token_mint.key()).0.as_ref(),
+            sorted_mints(&wsol_mint.key(), &token_mint.key()).1.as_ref(),
         ],
         seeds::program = raydium_program,
         bump,
     )]
     pub pool_state: UncheckedAccount<'info>,
 
-    /// CHECK: Pool observation state account
-    #[account(mut)]
+    /// CHECK: Pool observation state account, checked by address so a wrong one gets its own error
+    #[account(
+        mut,
+        address = Pubkey::find_program_address(
+            &[OBSERVATION_SEED.as_bytes(), pool_state.key().as_ref()],
+            &raydium_program.key(),
+        ).0 @ PumpFunError::InvalidObservationState,
+    )]
     pub observation_state: UncheckedAccount<'info>,
 
-    /// CHECK: Token vault for WSOL
+    /// CHECK: Token vault for mint 0
     #[account(
         mut,
         seeds = [
             POOL_VAULT_SEED.as_bytes(),
             pool_state.key().as_ref(),
-            wsol_mint.key().as_ref(),
+            sorted_mints(&wsol_mint.key(), &token_mint.key()).0.as_ref(),
         ],
         seeds::program = raydium_program,
         bump,
     )]
     pub token_vault_0: UncheckedAccount<'info>,
 
-    /// CHECK: Token vault for project token
+    /// CHECK: Token vault for mint 1
     #[account(
         mut,
         seeds = [
             POOL_VAULT_SEED.as_bytes(),
             pool_state.key().as_ref(),
-            token_mint.key().as_ref(),
+            sorted_mints(&wsol_mint.key(), &token_mint.key()).1.as_ref(),
         ],
         seeds::program = raydium_program,
         bump,
@@ -108,14 +123,71 @@ pub struct Migrate<'info> {
     )]
     pub tick_array_bitmap: UncheckedAccount<'info>,
 
-    /// CHECK: Fee recipient account
+    #[account(mut, seeds = [FeeStats::SEED_PREFIX.as_bytes()], bump)]
+    pub fee_stats: Box<Account<'info, FeeStats>>,
+
+    #[account(mut, seeds = [FeeStats::VAULT_SEED_PREFIX.as_bytes()], bump)]
+    pub fee_vault: SystemAccount<'info>,
+
+    /// CHECK: Data-less PDA that deposits the liquidity and holds the position NFT
+    #[account(
+        mut,
+        seeds = [BondingCurve::POSITION_AUTHORITY_SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub position_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = migrator,
+        associated_token::mint = wsol_mint,
+        associated_token::authority = position_authority,
+    )]
+    pub position_wsol_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = migrator,
+        associated_token::mint = token_mint,
+        associated_token::authority = position_authority,
+    )]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Fresh keypair, initialized by Raydium as the position NFT mint
+    #[account(mut)]
+    pub position_nft_mint: Signer<'info>,
+
+    /// CHECK: Position NFT account of the position authority, initialized by Raydium
+    #[account(mut)]
+    pub position_nft_account: UncheckedAccount<'info>,
+
+    /// CHECK: Position NFT metadata, initialized by Raydium
+    #[account(mut)]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: Verified and initialized by Raydium
+    #[account(mut)]
+    pub protocol_position: UncheckedAccount<'info>,
+
+    /// CHECK: Verified and initialized by Raydium
+    #[account(mut)]
+    pub personal_position: UncheckedAccount<'info>,
+
+    /// CHECK: Verified and initialized by Raydium
     #[account(mut)]
-    pub fee_recipient: AccountInfo<'info>,
+    pub tick_array_lower: UncheckedAccount<'info>,
 
-    /// CHECK: Raydium program state account
+    /// CHECK: Verified and initialized by Raydium
+    #[account(mut)]
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    // Only the allowlisted Raydium fee tier
+    #[account(constraint = amm_config.index == config.clmm_config_index @ PumpFunError::InvalidAmmConfig)]
     pub amm_config: Box<Account<'info, AmmConfig>>,
 
     pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub metadata_program: Program<'info, Metadata>,
     pub system_program: Program<'info, System>,
     pub raydium_program: Program<'info, AmmV3>,
     pub rent: Sysvar<'info, Rent>,
@@ -123,40 +195,64 @@ pub struct Migrate<'info> {
 
 impl<'info> Migrate<'info> {
     pub fn process(ctx: Context<Migrate>) -> Result<()> {
-        let bonding_curve = &mut ctx.accounts.bonding_curve;
-        let config = &ctx.accounts.config;
-
-        // Verify authority
-        require!(
-            config.authority == ctx.accounts.authority.key(),
-            PumpFunError::UnauthorizedAddress
-        );
-
-        // Calculate amounts and fees
-        let sol_balance = ctx.accounts.curve_sol_account.lamports();
-        let token_balance = ctx.accounts.curve_token_account.amount;
+        let migration = CurveMigration {
+            migrator: ctx.accounts.migrator.to_account_info(),
+            bonding_curve: ctx.accounts.bonding_curve.to_account_info(),
+            curve_token_account: ctx.accounts.curve_token_account.to_account_info(),
+            sol_vault: ctx.accounts.sol_vault.to_account_info(),
+            fee_vault: ctx.accounts.fee_vault.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_mint: ctx.accounts.token_mint.key(),
+            curve_bump: ctx.bumps.bonding_curve,
+            sol_vault_bump: ctx.bumps.sol_vault,
+        };
+        migration.authorize(&ctx.accounts.config)?;
 
-        // Calculate migration fee
-        let migration_fee = sol_balance
-            .checked_mul(config.migration_fee_percentage as u64)
-            .ok_or(PumpFunError::MathOverflow)?
-            .checked_div(100)
-            .ok_or(PumpFunError::MathOverflow)?;
+        // Amounts, pool order and price, exactly what preview_migration reports
+        let plan = MigrationPlan::compute(
+            &ctx.accounts.config,
+            &ctx.accounts.bonding_curve,
+            &ctx.accounts.token_mint.key(),
+            &ctx.accounts.wsol_mint.key(),
+            ctx.accounts.bonding_curve.real_sol_reserve,
+            ctx.accounts.curve_token_account.amount,
+        )?;
+        let MigrationPlan {
+            amounts,
+            token_is_mint_0,
+            amount_0,
+            amount_1,
+            sqrt_price_x64: init_sqrt_price,
+            ..
+        } = plan;
 
-        let remaining_sol = sol_balance
-            .checked_sub(migration_fee)
-            .ok_or(PumpFunError::InsufficientSolBalance)?;
+        let (mint_0, mint_1) = if token_is_mint_0 {
+            (ctx.accounts.token_mint.to_account_info(), ctx.accounts.wsol_mint.to_account_info())
+        } else {
+            (ctx.accounts.wsol_mint.to_account_info(), ctx.accounts.token_mint.to_account_info())
+        };
+        let (position_account_0, position_account_1) = if token_is_mint_0 {
+            (
+                ctx.accounts.position_token_account.to_account_info(),
+                ctx.accounts.position_wsol_account.to_account_info(),
+            )
+        } else {
+            (
+                ctx.accounts.position_wsol_account.to_account_info(),
+                ctx.accounts.position_token_account.to_account_info(),
+            )
+        };
 
-        // Create Raydium pool
-        let init_sqrt_price = tick_math::get_sqrt_price_at_tick(0)?; // Start at tick 0 for 1:1 price
+        // Create Raydium pool priced at the deposit
         let open_time = Clock::get()?.unix_timestamp as u64;
 
         let create_pool_accounts = raydium_amm_v3::cpi::accounts::CreatePool {
-            pool_creator: ctx.accounts.authority.to_account_info(),
+            pool_creator: ctx.accounts.migrator.to_account_info(),
             amm_config: ctx.accounts.amm_config.to_account_info(),
             pool_state: ctx.accounts.pool_state.to_account_info(),
-            token_mint_0: ctx.accounts.wsol_mint.to_account_info(),
-            token_mint_1: ctx.accounts.token_mint.to_account_info(),
+            token_mint_0: mint_0,
+            token_mint_1: mint_1,
             token_vault_0: ctx.accounts.token_vault_0.to_account_info(),
             token_vault_1: ctx.accounts.token_vault_1.to_account_info(),
             observation_state: ctx.accounts.observation_state.to_account_info(),
@@ -174,51 +270,89 @@ impl<'info> Migrate<'info> {
 
         raydium_amm_v3::cpi::create_pool(create_pool_ctx, init_sqrt_price, open_time)?;
 
-        // Get signer seeds for PDA operations
-        let bump = ctx.bumps.bonding_curve;
-        let token_key = ctx.accounts.token_mint.key();
-        let seeds = BondingCurve::get_signer(&token_key, &bump);
-        let signer_seeds = &[&seeds[..]];
-
-        // Transfer migration fee to fee recipient
-        sol_transfer_with_signer(
-            &ctx.accounts.curve_sol_account.to_account_info(),
-            &ctx.accounts.fee_recipient,
-            &ctx.accounts.system_program,
-            signer_seeds,
-            migration_fee,
+        // Fees out, then the remaining SOL wrapped next to the tokens
+        migration.pay_fees(&amounts, &mut ctx.accounts.fee_stats)?;
+        migration.release_reserves(
+            &amounts,
+            &ctx.accounts.position_wsol_account.to_account_info(),
+            &ctx.accounts.position_token_account.to_account_info(),
+            true,
         )?;
 
-        // Transfer remaining SOL to Raydium pool
-        sol_transfer_with_signer(
-            &ctx.accounts.curve_sol_account.to_account_info(),
-            &ctx.accounts.token_vault_0.to_account_info(), // Transfer to WSOL vault
-            &ctx.accounts.system_program,
-            signer_seeds,
-            remaining_sol,
+        // Position authority pays rent for the Raydium position accounts, the migrator fronts it
+        let authority_lamports = migration.front_position_budget(
+            &ctx.accounts.position_authority.to_account_info(),
+            POSITION_RENT_BUDGET,
         )?;
 
-        // Transfer tokens to Raydium pool
-        token_transfer_with_signer(
-            &ctx.accounts.curve_token_account.to_account_info(),
-            &bonding_curve.to_account_info(),
-            &ctx.accounts.token_vault_1.to_account_info(), // Transfer to token vault
-            &ctx.accounts.token_program.to_account_info(),
-            signer_seeds,
-            token_balance,
-        )?;
+        // Full range position, leaving a unit of headroom for Raydium rounding amounts up
+        let (tick_lower, tick_upper) = full_range_ticks(ctx.accounts.amm_config.tick_spacing);
+        let liquidity = liquidity_math::get_liquidity_from_amounts(
+            init_sqrt_price,
+            tick_math::get_sqrt_price_at_tick(tick_lower)?,
+            tick_math::get_sqrt_price_at_tick(tick_upper)?,
+            amount_0.saturating_sub(1),
+            amount_1.saturating_sub(1),
+        );
 
-        // Mark as migrated
-        bonding_curve.is_migrated = true;
+        let token_key = ctx.accounts.token_mint.key();
+        let position_bump = ctx.bumps.position_authority;
+        let position_seeds = BondingCurve::get_position_authority_signer(&token_key, &position_bump);
+        let position_signer_seeds = &[&position_seeds[..]];
 
-        emit!(MigrationCompleted {
-            token_mint: ctx.accounts.token_mint.key(),
-            sol_amount: remaining_sol,
-            token_amount: token_balance,
-            migration_fee: migration_fee,
-            raydium_pool: ctx.accounts.pool_state.key(),
-        });
+        let open_position_accounts = raydium_amm_v3::cpi::accounts::OpenPosition {
+            payer: ctx.accounts.position_authority.to_account_info(),
+            position_nft_owner: ctx.accounts.position_authority.to_account_info(),
+            position_nft_mint: ctx.accounts.position_nft_mint.to_account_info(),
+            position_nft_account: ctx.accounts.position_nft_account.to_account_info(),
+            metadata_account: ctx.accounts.metadata_account.to_account_info(),
+            pool_state: ctx.accounts.pool_state.to_account_info(),
+            protocol_position: ctx.accounts.protocol_position.to_account_info(),
+            tick_array_lower: ctx.accounts.tick_array_lower.to_account_info(),
+            tick_array_upper: ctx.accounts.tick_array_upper.to_account_info(),
+            personal_position: ctx.accounts.personal_position.to_account_info(),
+            token_account_0: position_account_0,
+            token_account_1: position_account_1,
+            token_vault_0: ctx.accounts.token_vault_0.to_account_info(),
+            token_vault_1: ctx.accounts.token_vault_1.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            metadata_program: ctx.accounts.metadata_program.to_account_info(),
+        };
+
+        let open_position_ctx = CpiContext::new_with_signer(
+            ctx.accounts.raydium_program.to_account_info(),
+            open_position_accounts,
+            position_signer_seeds,
+        );
+
+        let tick_spacing = ctx.accounts.amm_config.tick_spacing;
+        raydium_amm_v3::cpi::open_position(
+            open_position_ctx,
+            tick_lower,
+            tick_upper,
+            TickArrayState::get_array_start_index(tick_lower, tick_spacing),
+            TickArrayState::get_array_start_index(tick_upper, tick_spacing),
+            liquidity,
+            amount_0,
+            amount_1,
+        )?;
+
+        migration.refund_position_budget(
+            &ctx.accounts.position_authority.to_account_info(),
+            authority_lamports,
+            position_signer_seeds,
+        )?;
 
-        Ok(())
+        migration.complete(
+            &mut ctx.accounts.bonding_curve,
+            MigrationTarget::RaydiumClmm,
+            &amounts,
+            ctx.accounts.pool_state.key(),
+            ctx.accounts.position_nft_mint.key(),
+            liquidity,
+        )
     }
 }
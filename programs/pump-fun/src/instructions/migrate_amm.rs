@@ -0,0 +1,154 @@
+use crate::constants::AMM_FEE_BPS;
+use crate::errors::PumpFunError;
+use crate::states::{AmmPool, BondingCurve, Config, FeeStats, MigrationAmounts, MigrationTarget};
+use crate::utils::{constant_product_liquidity, sol_transfer_from_user, CurveMigration};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct MigrateAmm<'info> {
+    #[account(mut)]
+    pub migrator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump,
+        constraint = bonding_curve.is_completed @ PumpFunError::CurveNotCompleted,
+        constraint = !bonding_curve.is_migrated @ PumpFunError::AlreadyMigrated,
+        constraint = bonding_curve.real_sol_reserve > 0 @ PumpFunError::InsufficientSolBalance,
+        constraint = bonding_curve.migration_target == MigrationTarget::ConstantProduct @ PumpFunError::WrongMigrationTarget,
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = bonding_curve,
+        constraint = curve_token_account.amount > 0 @ PumpFunError::InsufficientTokenBalance,
+    )]
+    pub curve_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SOL_VAULT_SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    #[account(mut, seeds = [FeeStats::SEED_PREFIX.as_bytes()], bump)]
+    pub fee_stats: Box<Account<'info, FeeStats>>,
+
+    #[account(mut, seeds = [FeeStats::VAULT_SEED_PREFIX.as_bytes()], bump)]
+    pub fee_vault: SystemAccount<'info>,
+
+    #[account(
+        init,
+        payer = migrator,
+        space = 8 + AmmPool::LEN,
+        seeds = [AmmPool::SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub amm_pool: Box<Account<'info, AmmPool>>,
+
+    #[account(
+        mut,
+        seeds = [AmmPool::SOL_VAULT_SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub amm_sol_vault: SystemAccount<'info>,
+
+    #[account(
+        init,
+        payer = migrator,
+        associated_token::mint = token_mint,
+        associated_token::authority = amm_pool,
+    )]
+    pub amm_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateAmm<'info> {
+    pub fn process(ctx: Context<MigrateAmm>) -> Result<()> {
+        let migration = CurveMigration {
+            migrator: ctx.accounts.migrator.to_account_info(),
+            bonding_curve: ctx.accounts.bonding_curve.to_account_info(),
+            curve_token_account: ctx.accounts.curve_token_account.to_account_info(),
+            sol_vault: ctx.accounts.sol_vault.to_account_info(),
+            fee_vault: ctx.accounts.fee_vault.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_mint: ctx.accounts.token_mint.key(),
+            curve_bump: ctx.bumps.bonding_curve,
+            sol_vault_bump: ctx.bumps.sol_vault,
+        };
+        migration.authorize(&ctx.accounts.config)?;
+
+        // Calculate amounts and fees
+        let amounts = MigrationAmounts::compute(
+            ctx.accounts.bonding_curve.real_sol_reserve,
+            ctx.accounts.curve_token_account.amount,
+            ctx.accounts.config.migration_fee_percentage,
+            ctx.accounts.config.migration_bounty,
+        )?;
+        let MigrationAmounts {
+            token_balance,
+            remaining_sol,
+            ..
+        } = amounts;
+
+        migration.pay_fees(&amounts, &mut ctx.accounts.fee_stats)?;
+
+        // The migrator funds the pool's SOL vault rent so swaps can never pay it out
+        sol_transfer_from_user(
+            &ctx.accounts.migrator.to_account_info(),
+            &ctx.accounts.amm_sol_vault.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            Rent::get()?.minimum_balance(0),
+        )?;
+
+        // Seed the pool with the remaining SOL and every curve token
+        migration.release_reserves(
+            &amounts,
+            &ctx.accounts.amm_sol_vault.to_account_info(),
+            &ctx.accounts.amm_token_account.to_account_info(),
+            false,
+        )?;
+
+        // No LP tokens are minted, the seeded liquidity stays locked in the pool
+        let liquidity = constant_product_liquidity(remaining_sol, token_balance);
+
+        let amm_pool = &mut ctx.accounts.amm_pool;
+        amm_pool.token_mint = ctx.accounts.token_mint.key();
+        amm_pool.sol_reserve = remaining_sol;
+        amm_pool.token_reserve = token_balance;
+        amm_pool.locked_liquidity = liquidity;
+        amm_pool.fee_bps = AMM_FEE_BPS;
+        amm_pool.bump = ctx.bumps.amm_pool;
+        amm_pool.sol_vault_bump = ctx.bumps.amm_sol_vault;
+
+        migration.complete(
+            &mut ctx.accounts.bonding_curve,
+            MigrationTarget::ConstantProduct,
+            &amounts,
+            ctx.accounts.amm_pool.key(),
+            Pubkey::default(),
+            liquidity,
+        )
+    }
+}
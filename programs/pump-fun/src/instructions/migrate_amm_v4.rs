@@ -0,0 +1,284 @@
+use crate::constants::POSITION_RENT_BUDGET;
+use crate::errors::PumpFunError;
+use crate::states::{BondingCurve, Config, FeeStats, MigrationAmounts, MigrationTarget};
+use crate::utils::{
+    raydium_amm_v4::{
+        self, Initialize2, AMM_ASSOCIATED_SEED, AMM_AUTHORITY_SEED, AMM_CONFIG_SEED,
+        COIN_VAULT_ASSOCIATED_SEED, LP_MINT_ASSOCIATED_SEED, OPEN_ORDER_ASSOCIATED_SEED,
+        PC_VAULT_ASSOCIATED_SEED, TARGET_ASSOCIATED_SEED,
+    },
+    CurveMigration,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{spl_token::native_mint, Mint, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct MigrateAmmV4<'info> {
+    #[account(mut)]
+    pub migrator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump,
+        constraint = bonding_curve.is_completed @ PumpFunError::CurveNotCompleted,
+        constraint = !bonding_curve.is_migrated @ PumpFunError::AlreadyMigrated,
+        constraint = bonding_curve.real_sol_reserve > 0 @ PumpFunError::InsufficientSolBalance,
+        constraint = bonding_curve.migration_target == MigrationTarget::RaydiumAmmV4 @ PumpFunError::WrongMigrationTarget,
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    #[account(address = native_mint::ID @ PumpFunError::InvalidWsolMint)]
+    pub wsol_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = bonding_curve,
+        constraint = curve_token_account.amount > 0 @ PumpFunError::InsufficientTokenBalance,
+    )]
+    pub curve_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SOL_VAULT_SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    #[account(mut, seeds = [FeeStats::SEED_PREFIX.as_bytes()], bump)]
+    pub fee_stats: Box<Account<'info, FeeStats>>,
+
+    #[account(mut, seeds = [FeeStats::VAULT_SEED_PREFIX.as_bytes()], bump)]
+    pub fee_vault: SystemAccount<'info>,
+
+    /// CHECK: Data-less PDA that deposits the liquidity and holds the LP tokens
+    #[account(
+        mut,
+        seeds = [BondingCurve::POSITION_AUTHORITY_SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub position_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = migrator,
+        associated_token::mint = wsol_mint,
+        associated_token::authority = position_authority,
+    )]
+    pub position_wsol_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = migrator,
+        associated_token::mint = token_mint,
+        associated_token::authority = position_authority,
+    )]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: LP token account of the position authority, initialized by Raydium
+    #[account(mut)]
+    pub position_lp_account: UncheckedAccount<'info>,
+
+    /// CHECK: Raydium's single AMM config, read for the pool creation fee
+    #[account(
+        seeds = [AMM_CONFIG_SEED.as_bytes()],
+        seeds::program = raydium_program.key(),
+        bump,
+    )]
+    pub amm_config: UncheckedAccount<'info>,
+
+    /// CHECK: Raydium vault and LP mint authority
+    #[account(seeds = [AMM_AUTHORITY_SEED.as_bytes()], seeds::program = raydium_program.key(), bump)]
+    pub raydium_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Pool state account to be created
+    #[account(
+        mut,
+        seeds = [raydium_program.key().as_ref(), market.key().as_ref(), AMM_ASSOCIATED_SEED.as_bytes()],
+        seeds::program = raydium_program.key(),
+        bump,
+    )]
+    pub pool_state: UncheckedAccount<'info>,
+
+    /// CHECK: Pool open orders account to be created
+    #[account(
+        mut,
+        seeds = [raydium_program.key().as_ref(), market.key().as_ref(), OPEN_ORDER_ASSOCIATED_SEED.as_bytes()],
+        seeds::program = raydium_program.key(),
+        bump,
+    )]
+    pub open_orders: UncheckedAccount<'info>,
+
+    /// CHECK: LP mint to be created
+    #[account(
+        mut,
+        seeds = [raydium_program.key().as_ref(), market.key().as_ref(), LP_MINT_ASSOCIATED_SEED.as_bytes()],
+        seeds::program = raydium_program.key(),
+        bump,
+    )]
+    pub lp_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Token vault, the project token is the coin side
+    #[account(
+        mut,
+        seeds = [raydium_program.key().as_ref(), market.key().as_ref(), COIN_VAULT_ASSOCIATED_SEED.as_bytes()],
+        seeds::program = raydium_program.key(),
+        bump,
+    )]
+    pub coin_vault: UncheckedAccount<'info>,
+
+    /// CHECK: WSOL vault, SOL is the pc side
+    #[account(
+        mut,
+        seeds = [raydium_program.key().as_ref(), market.key().as_ref(), PC_VAULT_ASSOCIATED_SEED.as_bytes()],
+        seeds::program = raydium_program.key(),
+        bump,
+    )]
+    pub pc_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Pool target orders account to be created
+    #[account(
+        mut,
+        seeds = [raydium_program.key().as_ref(), market.key().as_ref(), TARGET_ASSOCIATED_SEED.as_bytes()],
+        seeds::program = raydium_program.key(),
+        bump,
+    )]
+    pub target_orders: UncheckedAccount<'info>,
+
+    /// CHECK: Raydium pool creation fee receiver, checked by Raydium
+    #[account(mut)]
+    pub create_pool_fee: UncheckedAccount<'info>,
+
+    /// CHECK: OpenBook market for the token/WSOL pair, listed beforehand and checked by Raydium
+    pub market: UncheckedAccount<'info>,
+
+    /// CHECK: OpenBook program the market belongs to
+    #[account(address = raydium_amm_v4::market_program::ID)]
+    pub market_program: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: Raydium AMM v4, a native program
+    #[account(address = raydium_amm_v4::program::ID)]
+    pub raydium_program: UncheckedAccount<'info>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> MigrateAmmV4<'info> {
+    pub fn process(ctx: Context<MigrateAmmV4>) -> Result<()> {
+        let migration = CurveMigration {
+            migrator: ctx.accounts.migrator.to_account_info(),
+            bonding_curve: ctx.accounts.bonding_curve.to_account_info(),
+            curve_token_account: ctx.accounts.curve_token_account.to_account_info(),
+            sol_vault: ctx.accounts.sol_vault.to_account_info(),
+            fee_vault: ctx.accounts.fee_vault.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_mint: ctx.accounts.token_mint.key(),
+            curve_bump: ctx.bumps.bonding_curve,
+            sol_vault_bump: ctx.bumps.sol_vault,
+        };
+        migration.authorize(&ctx.accounts.config)?;
+
+        // Calculate amounts and fees
+        let amounts = MigrationAmounts::compute(
+            ctx.accounts.bonding_curve.real_sol_reserve,
+            ctx.accounts.curve_token_account.amount,
+            ctx.accounts.config.migration_fee_percentage,
+            ctx.accounts.config.migration_bounty,
+        )?;
+
+        // Fees out, then the remaining SOL wrapped next to the tokens
+        migration.pay_fees(&amounts, &mut ctx.accounts.fee_stats)?;
+        migration.release_reserves(
+            &amounts,
+            &ctx.accounts.position_wsol_account.to_account_info(),
+            &ctx.accounts.position_token_account.to_account_info(),
+            true,
+        )?;
+
+        // Position authority creates the pool, the migrator fronts rent and Raydium's creation fee
+        let creation_lamports = POSITION_RENT_BUDGET
+            .checked_add(raydium_amm_v4::create_pool_fee(&ctx.accounts.amm_config)?)
+            .ok_or(PumpFunError::MathOverflow)?;
+        let authority_lamports = migration.front_position_budget(
+            &ctx.accounts.position_authority.to_account_info(),
+            creation_lamports,
+        )?;
+
+        let token_key = ctx.accounts.token_mint.key();
+        let position_bump = ctx.bumps.position_authority;
+        let position_seeds = BondingCurve::get_position_authority_signer(&token_key, &position_bump);
+        let position_signer_seeds = &[&position_seeds[..]];
+
+        // AMM v4 quotes the project token (coin) in WSOL (pc)
+        let initialize = Initialize2 {
+            token_program: &ctx.accounts.token_program.to_account_info(),
+            associated_token_program: &ctx.accounts.associated_token_program.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+            rent: &ctx.accounts.rent.to_account_info(),
+            amm: &ctx.accounts.pool_state.to_account_info(),
+            amm_authority: &ctx.accounts.raydium_authority.to_account_info(),
+            amm_open_orders: &ctx.accounts.open_orders.to_account_info(),
+            lp_mint: &ctx.accounts.lp_mint.to_account_info(),
+            coin_mint: &ctx.accounts.token_mint.to_account_info(),
+            pc_mint: &ctx.accounts.wsol_mint.to_account_info(),
+            coin_vault: &ctx.accounts.coin_vault.to_account_info(),
+            pc_vault: &ctx.accounts.pc_vault.to_account_info(),
+            target_orders: &ctx.accounts.target_orders.to_account_info(),
+            amm_config: &ctx.accounts.amm_config.to_account_info(),
+            create_fee_destination: &ctx.accounts.create_pool_fee.to_account_info(),
+            market_program: &ctx.accounts.market_program.to_account_info(),
+            market: &ctx.accounts.market.to_account_info(),
+            user_wallet: &ctx.accounts.position_authority.to_account_info(),
+            user_token_coin: &ctx.accounts.position_token_account.to_account_info(),
+            user_token_pc: &ctx.accounts.position_wsol_account.to_account_info(),
+            user_token_lp: &ctx.accounts.position_lp_account.to_account_info(),
+        };
+
+        let open_time = Clock::get()?.unix_timestamp as u64;
+        initialize.invoke_signed(
+            &ctx.accounts.raydium_program.to_account_info(),
+            ctx.bumps.raydium_authority,
+            open_time,
+            amounts.remaining_sol,
+            amounts.token_balance,
+            position_signer_seeds,
+        )?;
+
+        migration.refund_position_budget(
+            &ctx.accounts.position_authority.to_account_info(),
+            authority_lamports,
+            position_signer_seeds,
+        )?;
+
+        // LP tokens stay with the position authority
+        let liquidity = {
+            let data = ctx.accounts.position_lp_account.try_borrow_data()?;
+            TokenAccount::try_deserialize(&mut &data[..])?.amount as u128
+        };
+
+        migration.complete(
+            &mut ctx.accounts.bonding_curve,
+            MigrationTarget::RaydiumAmmV4,
+            &amounts,
+            ctx.accounts.pool_state.key(),
+            ctx.accounts.lp_mint.key(),
+            liquidity,
+        )
+    }
+}
@@ -0,0 +1,294 @@
+use crate::constants::POSITION_RENT_BUDGET;
+use crate::errors::PumpFunError;
+use crate::states::{
+    sorted_mints, BondingCurve, Config, FeeStats, MigrationAmounts, MigrationTarget,
+};
+use crate::utils::CurveMigration;
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{spl_token::native_mint, Mint, Token, TokenAccount},
+};
+use raydium_cp_swap::{
+    self,
+    program::RaydiumCpSwap,
+    states::{AmmConfig, OBSERVATION_SEED, POOL_LP_MINT_SEED, POOL_SEED, POOL_VAULT_SEED},
+    AUTH_SEED,
+};
+
+#[derive(Accounts)]
+pub struct MigrateCpmm<'info> {
+    #[account(mut)]
+    pub migrator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump,
+        constraint = bonding_curve.is_completed @ PumpFunError::CurveNotCompleted,
+        constraint = !bonding_curve.is_migrated @ PumpFunError::AlreadyMigrated,
+        constraint = bonding_curve.real_sol_reserve > 0 @ PumpFunError::InsufficientSolBalance,
+        constraint = bonding_curve.migration_target == MigrationTarget::RaydiumCpmm @ PumpFunError::WrongMigrationTarget,
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    #[account(address = native_mint::ID @ PumpFunError::InvalidWsolMint)]
+    pub wsol_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = bonding_curve,
+        constraint = curve_token_account.amount > 0 @ PumpFunError::InsufficientTokenBalance,
+    )]
+    pub curve_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SOL_VAULT_SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    #[account(mut, seeds = [FeeStats::SEED_PREFIX.as_bytes()], bump)]
+    pub fee_stats: Box<Account<'info, FeeStats>>,
+
+    #[account(mut, seeds = [FeeStats::VAULT_SEED_PREFIX.as_bytes()], bump)]
+    pub fee_vault: SystemAccount<'info>,
+
+    /// CHECK: Data-less PDA that deposits the liquidity and holds the LP tokens
+    #[account(
+        mut,
+        seeds = [BondingCurve::POSITION_AUTHORITY_SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub position_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = migrator,
+        associated_token::mint = wsol_mint,
+        associated_token::authority = position_authority,
+    )]
+    pub position_wsol_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = migrator,
+        associated_token::mint = token_mint,
+        associated_token::authority = position_authority,
+    )]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: LP token account of the position authority, initialized by Raydium
+    #[account(mut)]
+    pub position_lp_account: UncheckedAccount<'info>,
+
+    // Only the allowlisted Raydium fee tier
+    #[account(constraint = amm_config.index == config.cpmm_config_index @ PumpFunError::InvalidAmmConfig)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// CHECK: Raydium vault and LP mint authority
+    #[account(seeds = [AUTH_SEED.as_bytes()], seeds::program = raydium_program, bump)]
+    pub raydium_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Pool state account to be created
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+            sorted_mints(&wsol_mint.key(), &token_mint.key()).0.as_ref(),
+            sorted_mints(&wsol_mint.key(), &token_mint.key()).1.as_ref(),
+        ],
+        seeds::program = raydium_program,
+        bump,
+    )]
+    pub pool_state: UncheckedAccount<'info>,
+
+    /// CHECK: LP mint to be created
+    #[account(
+        mut,
+        seeds = [POOL_LP_MINT_SEED.as_bytes(), pool_state.key().as_ref()],
+        seeds::program = raydium_program,
+        bump,
+    )]
+    pub lp_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Token vault for mint 0
+    #[account(
+        mut,
+        seeds = [
+            POOL_VAULT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            sorted_mints(&wsol_mint.key(), &token_mint.key()).0.as_ref(),
+        ],
+        seeds::program = raydium_program,
+        bump,
+    )]
+    pub token_vault_0: UncheckedAccount<'info>,
+
+    /// CHECK: Token vault for mint 1
+    #[account(
+        mut,
+        seeds = [
+            POOL_VAULT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            sorted_mints(&wsol_mint.key(), &token_mint.key()).1.as_ref(),
+        ],
+        seeds::program = raydium_program,
+        bump,
+    )]
+    pub token_vault_1: UncheckedAccount<'info>,
+
+    /// CHECK: Raydium pool creation fee receiver, checked by Raydium
+    #[account(mut)]
+    pub create_pool_fee: UncheckedAccount<'info>,
+
+    /// CHECK: Pool observation state account, checked by address so a wrong one gets its own error
+    #[account(
+        mut,
+        address = Pubkey::find_program_address(
+            &[OBSERVATION_SEED.as_bytes(), pool_state.key().as_ref()],
+            &raydium_program.key(),
+        ).0 @ PumpFunError::InvalidObservationState,
+    )]
+    pub observation_state: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub raydium_program: Program<'info, RaydiumCpSwap>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> MigrateCpmm<'info> {
+    pub fn process(ctx: Context<MigrateCpmm>) -> Result<()> {
+        let migration = CurveMigration {
+            migrator: ctx.accounts.migrator.to_account_info(),
+            bonding_curve: ctx.accounts.bonding_curve.to_account_info(),
+            curve_token_account: ctx.accounts.curve_token_account.to_account_info(),
+            sol_vault: ctx.accounts.sol_vault.to_account_info(),
+            fee_vault: ctx.accounts.fee_vault.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_mint: ctx.accounts.token_mint.key(),
+            curve_bump: ctx.bumps.bonding_curve,
+            sol_vault_bump: ctx.bumps.sol_vault,
+        };
+        migration.authorize(&ctx.accounts.config)?;
+
+        // Calculate amounts and fees
+        let amounts = MigrationAmounts::compute(
+            ctx.accounts.bonding_curve.real_sol_reserve,
+            ctx.accounts.curve_token_account.amount,
+            ctx.accounts.config.migration_fee_percentage,
+            ctx.accounts.config.migration_bounty,
+        )?;
+
+        // Raydium orders the pool by mint, the project token may come first
+        let token_is_mint_0 = ctx.accounts.token_mint.key() < ctx.accounts.wsol_mint.key();
+        let (amount_0, amount_1) = amounts.pool_amounts(token_is_mint_0);
+        let (mint_0, mint_1) = if token_is_mint_0 {
+            (ctx.accounts.token_mint.to_account_info(), ctx.accounts.wsol_mint.to_account_info())
+        } else {
+            (ctx.accounts.wsol_mint.to_account_info(), ctx.accounts.token_mint.to_account_info())
+        };
+        let (position_account_0, position_account_1) = if token_is_mint_0 {
+            (
+                ctx.accounts.position_token_account.to_account_info(),
+                ctx.accounts.position_wsol_account.to_account_info(),
+            )
+        } else {
+            (
+                ctx.accounts.position_wsol_account.to_account_info(),
+                ctx.accounts.position_token_account.to_account_info(),
+            )
+        };
+
+        // Fees out, then the remaining SOL wrapped next to the tokens
+        migration.pay_fees(&amounts, &mut ctx.accounts.fee_stats)?;
+        migration.release_reserves(
+            &amounts,
+            &ctx.accounts.position_wsol_account.to_account_info(),
+            &ctx.accounts.position_token_account.to_account_info(),
+            true,
+        )?;
+
+        // Position authority creates the pool, the migrator fronts rent and Raydium's creation fee
+        let creation_lamports = POSITION_RENT_BUDGET
+            .checked_add(ctx.accounts.amm_config.create_pool_fee)
+            .ok_or(PumpFunError::MathOverflow)?;
+        let authority_lamports = migration.front_position_budget(
+            &ctx.accounts.position_authority.to_account_info(),
+            creation_lamports,
+        )?;
+
+        let token_key = ctx.accounts.token_mint.key();
+        let position_bump = ctx.bumps.position_authority;
+        let position_seeds = BondingCurve::get_position_authority_signer(&token_key, &position_bump);
+        let position_signer_seeds = &[&position_seeds[..]];
+
+        let initialize_accounts = raydium_cp_swap::cpi::accounts::Initialize {
+            creator: ctx.accounts.position_authority.to_account_info(),
+            amm_config: ctx.accounts.amm_config.to_account_info(),
+            authority: ctx.accounts.raydium_authority.to_account_info(),
+            pool_state: ctx.accounts.pool_state.to_account_info(),
+            token_0_mint: mint_0,
+            token_1_mint: mint_1,
+            lp_mint: ctx.accounts.lp_mint.to_account_info(),
+            creator_token_0: position_account_0,
+            creator_token_1: position_account_1,
+            creator_lp_token: ctx.accounts.position_lp_account.to_account_info(),
+            token_0_vault: ctx.accounts.token_vault_0.to_account_info(),
+            token_1_vault: ctx.accounts.token_vault_1.to_account_info(),
+            create_pool_fee: ctx.accounts.create_pool_fee.to_account_info(),
+            observation_state: ctx.accounts.observation_state.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            token_0_program: ctx.accounts.token_program.to_account_info(),
+            token_1_program: ctx.accounts.token_program.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        };
+
+        let initialize_ctx = CpiContext::new_with_signer(
+            ctx.accounts.raydium_program.to_account_info(),
+            initialize_accounts,
+            position_signer_seeds,
+        );
+
+        let open_time = Clock::get()?.unix_timestamp as u64;
+        raydium_cp_swap::cpi::initialize(initialize_ctx, amount_0, amount_1, open_time)?;
+
+        migration.refund_position_budget(
+            &ctx.accounts.position_authority.to_account_info(),
+            authority_lamports,
+            position_signer_seeds,
+        )?;
+
+        // LP tokens stay with the position authority
+        let liquidity = {
+            let data = ctx.accounts.position_lp_account.try_borrow_data()?;
+            TokenAccount::try_deserialize(&mut &data[..])?.amount as u128
+        };
+
+        migration.complete(
+            &mut ctx.accounts.bonding_curve,
+            MigrationTarget::RaydiumCpmm,
+            &amounts,
+            ctx.accounts.pool_state.key(),
+            ctx.accounts.lp_mint.key(),
+            liquidity,
+        )
+    }
+}
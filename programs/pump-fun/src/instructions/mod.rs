@@ -9,3 +9,75 @@ pub use swap::*;
 
 pub mod migrate;
 pub use migrate::*;
+
+pub mod migrate_cpmm;
+pub use migrate_cpmm::*;
+
+pub mod migrate_amm;
+pub use migrate_amm::*;
+
+pub mod migrate_amm_v4;
+pub use migrate_amm_v4::*;
+
+pub mod amm_swap;
+pub use amm_swap::*;
+
+pub mod place_order;
+pub use place_order::*;
+
+pub mod fill_order;
+pub use fill_order::*;
+
+pub mod cancel_order;
+pub use cancel_order::*;
+
+pub mod create_dca;
+pub use create_dca::*;
+
+pub mod execute_dca;
+pub use execute_dca::*;
+
+pub mod cancel_dca;
+pub use cancel_dca::*;
+
+pub mod place_trigger_order;
+pub use place_trigger_order::*;
+
+pub mod execute_trigger_order;
+pub use execute_trigger_order::*;
+
+pub mod cancel_trigger_order;
+pub use cancel_trigger_order::*;
+
+pub mod refund;
+pub use refund::*;
+
+pub mod finalize;
+pub use finalize::*;
+
+pub mod preview_migration;
+pub use preview_migration::*;
+
+pub mod collect_pool_fees;
+pub use collect_pool_fees::*;
+
+pub mod audit_curve;
+pub use audit_curve::*;
+
+pub mod set_curve_pause;
+pub use set_curve_pause::*;
+
+pub mod claim_creator_fees;
+pub use claim_creator_fees::*;
+
+pub mod register_referrer;
+pub use register_referrer::*;
+
+pub mod claim_referral_fees;
+pub use claim_referral_fees::*;
+
+pub mod withdraw_fees;
+pub use withdraw_fees::*;
+
+pub mod close_trade_tracker;
+pub use close_trade_tracker::*;
@@ -0,0 +1,139 @@
+use crate::{
+    errors::PumpFunError,
+    states::{BondingCurve, Config, LimitOrder, Referrer},
+    utils::{fund_order_escrow, OrderPlaced},
+};
+
+use anchor_lang::{prelude::*, system_program};
+
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct PlaceOrder<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+
+    #[account(seeds = [Config::SEED_PREFIX.as_bytes()], bump)]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(seeds = [BondingCurve::SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump)]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + LimitOrder::LEN,
+        seeds = [
+            LimitOrder::SEED_PREFIX.as_bytes(),
+            &token_mint.key().to_bytes(),
+            &owner.key().to_bytes(),
+            &order_id.to_le_bytes(),
+        ],
+        bump
+    )]
+    limit_order: Box<Account<'info, LimitOrder>>,
+
+    #[account(init, payer = owner, associated_token::mint = token_mint, associated_token::authority = limit_order)]
+    order_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(init_if_needed, payer = owner, associated_token::mint = token_mint, associated_token::authority = owner)]
+    owner_token_account: Box<Account<'info, TokenAccount>>,
+
+    // Optional frontend referrer, credited when the order fills
+    #[account(
+        seeds = [Referrer::SEED_PREFIX.as_bytes(), &referrer.owner.to_bytes()],
+        bump = referrer.bump,
+        constraint = referrer.owner != owner.key() @ PumpFunError::SelfReferral
+    )]
+    referrer: Option<Box<Account<'info, Referrer>>>,
+
+    #[account(address = token::ID)]
+    token_program: Program<'info, Token>,
+    #[account(address = associated_token::ID)]
+    associated_token_program: Program<'info, AssociatedToken>,
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> PlaceOrder<'info> {
+    pub fn process(
+        &mut self,
+        order_id: u64,
+        direction: u8,
+        amount: u64,
+        limit_price: u64,
+        min_amount_out: u64,
+        expires_at: i64,
+        bump_order: u8,
+    ) -> Result<()> {
+        require!(
+            self.bonding_curve.is_completed == false,
+            PumpFunError::CurveLimitReached
+        );
+        require!(direction <= 1, PumpFunError::IncorrectValueRange);
+        require!(
+            amount > 0 && limit_price > 0,
+            PumpFunError::IncorrectValueRange
+        );
+        require!(
+            expires_at > Clock::get()?.unix_timestamp,
+            PumpFunError::OrderExpired
+        );
+
+        let crank_fee = self.global_config.crank_fee;
+
+        let limit_order = &mut self.limit_order;
+        limit_order.owner = self.owner.key();
+        limit_order.token_mint = self.token_mint.key();
+        limit_order.order_id = order_id;
+        limit_order.direction = direction;
+        limit_order.amount = amount;
+        limit_order.limit_price = limit_price;
+        limit_order.min_amount_out = min_amount_out;
+        limit_order.crank_fee = crank_fee;
+        limit_order.expires_at = expires_at;
+        limit_order.referrer = self.referrer.as_ref().map(|referrer| referrer.key());
+        limit_order.bump = bump_order;
+
+        let order_pda = limit_order.to_account_info();
+        let owner = self.owner.to_account_info();
+
+        // Escrow the crank fee, plus the SOL to spend on a buy or the tokens to sell
+        let (escrow_lamports, escrow_tokens) = if direction == 0 {
+            let lamports = amount
+                .checked_add(crank_fee)
+                .ok_or(PumpFunError::MathOverflow)?;
+            (lamports, 0)
+        } else {
+            (crank_fee, amount)
+        };
+        fund_order_escrow(
+            &owner,
+            &order_pda,
+            &self.owner_token_account.to_account_info(),
+            &self.order_token_account.to_account_info(),
+            &self.system_program.to_account_info(),
+            &self.token_program.to_account_info(),
+            escrow_lamports,
+            escrow_tokens,
+        )?;
+
+        emit!(OrderPlaced {
+            order: order_pda.key(),
+            owner: owner.key(),
+            token_mint: self.token_mint.key(),
+            direction,
+            amount,
+            limit_price,
+            expires_at,
+        });
+
+        Ok(())
+    }
+}
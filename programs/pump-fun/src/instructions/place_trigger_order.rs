@@ -0,0 +1,130 @@
+use crate::{
+    errors::PumpFunError,
+    states::{BondingCurve, Config, Referrer, TriggerOrder},
+    utils::{fund_order_escrow, TriggerOrderPlaced},
+};
+
+use anchor_lang::{prelude::*, system_program};
+
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct PlaceTriggerOrder<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+
+    #[account(seeds = [Config::SEED_PREFIX.as_bytes()], bump)]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(seeds = [BondingCurve::SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump)]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + TriggerOrder::LEN,
+        seeds = [
+            TriggerOrder::SEED_PREFIX.as_bytes(),
+            &token_mint.key().to_bytes(),
+            &owner.key().to_bytes(),
+            &order_id.to_le_bytes(),
+        ],
+        bump
+    )]
+    trigger_order: Box<Account<'info, TriggerOrder>>,
+
+    #[account(init, payer = owner, associated_token::mint = token_mint, associated_token::authority = trigger_order)]
+    order_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = token_mint, associated_token::authority = owner)]
+    owner_token_account: Box<Account<'info, TokenAccount>>,
+
+    // Optional frontend referrer, credited when the order executes
+    #[account(
+        seeds = [Referrer::SEED_PREFIX.as_bytes(), &referrer.owner.to_bytes()],
+        bump = referrer.bump,
+        constraint = referrer.owner != owner.key() @ PumpFunError::SelfReferral
+    )]
+    referrer: Option<Box<Account<'info, Referrer>>>,
+
+    #[account(address = token::ID)]
+    token_program: Program<'info, Token>,
+    #[account(address = associated_token::ID)]
+    associated_token_program: Program<'info, AssociatedToken>,
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> PlaceTriggerOrder<'info> {
+    pub fn process(
+        &mut self,
+        order_id: u64,
+        amount: u64,
+        stop_price: u64,
+        take_profit_price: u64,
+        min_amount_out: u64,
+        bump_order: u8,
+    ) -> Result<()> {
+        require!(
+            self.bonding_curve.is_completed == false,
+            PumpFunError::CurveLimitReached
+        );
+        require!(amount > 0, PumpFunError::IncorrectValueRange);
+
+        // Need at least one side, and the stop has to sit below the target
+        require!(
+            stop_price > 0 || take_profit_price > 0,
+            PumpFunError::IncorrectValueRange
+        );
+        require!(
+            stop_price == 0 || take_profit_price == 0 || stop_price < take_profit_price,
+            PumpFunError::IncorrectValueRange
+        );
+
+        let crank_fee = self.global_config.crank_fee;
+
+        let trigger_order = &mut self.trigger_order;
+        trigger_order.owner = self.owner.key();
+        trigger_order.token_mint = self.token_mint.key();
+        trigger_order.order_id = order_id;
+        trigger_order.amount = amount;
+        trigger_order.stop_price = stop_price;
+        trigger_order.take_profit_price = take_profit_price;
+        trigger_order.min_amount_out = min_amount_out;
+        trigger_order.crank_fee = crank_fee;
+        trigger_order.referrer = self.referrer.as_ref().map(|referrer| referrer.key());
+        trigger_order.bump = bump_order;
+
+        let order_pda = trigger_order.to_account_info();
+        let owner = self.owner.to_account_info();
+
+        // Escrow the crank fee and the tokens to sell
+        fund_order_escrow(
+            &owner,
+            &order_pda,
+            &self.owner_token_account.to_account_info(),
+            &self.order_token_account.to_account_info(),
+            &self.system_program.to_account_info(),
+            &self.token_program.to_account_info(),
+            crank_fee,
+            amount,
+        )?;
+
+        emit!(TriggerOrderPlaced {
+            order: order_pda.key(),
+            owner: owner.key(),
+            token_mint: self.token_mint.key(),
+            amount,
+            stop_price,
+            take_profit_price,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+use crate::errors::PumpFunError;
+use crate::states::{BondingCurve, Config, MigrationPlan, MigrationPreview, MigrationTarget};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{spl_token::native_mint, Mint, TokenAccount};
+use raydium_amm_v3::states::AmmConfig;
+
+#[derive(Accounts)]
+pub struct PreviewMigration<'info> {
+    #[account(
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    // Completion is not required so a curve can be previewed before it graduates.
+    // Only CLMM migrations are previewed, the other targets deposit the plain
+    // MigrationAmounts split and have no price or tick to derive.
+    #[account(
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump,
+        constraint = !bonding_curve.is_migrated @ PumpFunError::AlreadyMigrated,
+        constraint = bonding_curve.migration_target == MigrationTarget::RaydiumClmm @ PumpFunError::PreviewNotSupported,
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    #[account(address = native_mint::ID @ PumpFunError::InvalidWsolMint)]
+    pub wsol_mint: Box<Account<'info, Mint>>,
+
+    #[account(associated_token::mint = token_mint, associated_token::authority = bonding_curve)]
+    pub curve_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(constraint = amm_config.index == config.clmm_config_index @ PumpFunError::InvalidAmmConfig)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+}
+
+impl<'info> PreviewMigration<'info> {
+    pub fn process(ctx: Context<PreviewMigration>) -> Result<MigrationPreview> {
+        let token_mint = ctx.accounts.token_mint.key();
+        let wsol_mint = ctx.accounts.wsol_mint.key();
+
+        // Same computation migrate runs, so the preview can not drift from it
+        let plan = MigrationPlan::compute(
+            &ctx.accounts.config,
+            &ctx.accounts.bonding_curve,
+            &token_mint,
+            &wsol_mint,
+            ctx.accounts.bonding_curve.real_sol_reserve,
+            ctx.accounts.curve_token_account.amount,
+        )?;
+
+        // Anchor hands the preview back as return data
+        Ok(MigrationPreview::new(
+            plan,
+            &ctx.accounts.amm_config.key(),
+            &token_mint,
+            &wsol_mint,
+        ))
+    }
+}
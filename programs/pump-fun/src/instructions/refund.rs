@@ -0,0 +1,144 @@
+use crate::{
+    errors::PumpFunError,
+    states::{BondingCurve, RefundClaim},
+    utils::{sol_transfer_from_vault, token_burn_user, CurveRefunding, RefundClaimed},
+};
+
+use anchor_lang::{prelude::*, system_program};
+
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct Refund<'info> {
+    #[account(mut)]
+    holder: Signer<'info>,
+
+    #[account(mut, seeds = [BondingCurve::SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump)]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(mut, seeds = [BondingCurve::SOL_VAULT_SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump)]
+    sol_vault: SystemAccount<'info>,
+
+    #[account(mut)]
+    token_mint: Box<Account<'info, Mint>>,
+
+    #[account(associated_token::mint = token_mint, associated_token::authority = bonding_curve)]
+    curve_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = token_mint, token::authority = holder)]
+    holder_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = holder,
+        space = 8 + RefundClaim::LEN,
+        seeds = [
+            RefundClaim::SEED_PREFIX.as_bytes(),
+            &token_mint.key().to_bytes(),
+            &holder.key().to_bytes(),
+        ],
+        bump
+    )]
+    refund_claim: Box<Account<'info, RefundClaim>>,
+
+    #[account(address = token::ID)]
+    token_program: Program<'info, Token>,
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> Refund<'info> {
+    pub fn process(
+        &mut self,
+        amount: u64,
+        bump_sol_vault: u8,
+        bump_refund_claim: u8,
+    ) -> Result<()> {
+        let bonding_curve = &mut self.bonding_curve;
+        require!(amount > 0, PumpFunError::IncorrectValueRange);
+
+        // First redemption after the deadline switches the curve into refunding
+        if !bonding_curve.is_refunding {
+            require!(
+                bonding_curve.is_past_deadline(Clock::get()?.unix_timestamp),
+                PumpFunError::RefundNotAvailable
+            );
+
+            // Everything outside the curve's token account is redeemable
+            bonding_curve.is_refunding = true;
+            bonding_curve.refund_sol_reserve = bonding_curve.real_sol_reserve;
+            bonding_curve.refund_token_supply = bonding_curve
+                .token_total_supply
+                .checked_sub(self.curve_token_account.amount)
+                .ok_or(PumpFunError::InvalidReserves)?;
+
+            emit!(CurveRefunding {
+                token_mint: self.token_mint.key(),
+                graduation_deadline: bonding_curve.graduation_deadline,
+                refund_sol_reserve: bonding_curve.refund_sol_reserve,
+                refund_token_supply: bonding_curve.refund_token_supply,
+            });
+        }
+
+        let refunded_tokens = bonding_curve
+            .refunded_tokens
+            .checked_add(amount)
+            .ok_or(PumpFunError::MathOverflow)?;
+        require!(
+            refunded_tokens <= bonding_curve.refund_token_supply,
+            PumpFunError::InsufficientTokenBalance
+        );
+
+        let sol_amount = bonding_curve.refund_amount(amount)?;
+
+        // Burned tokens can never be redeemed twice
+        token_burn_user(
+            &self.token_mint.to_account_info(),
+            &self.holder_token_account.to_account_info(),
+            &self.holder.to_account_info(),
+            &self.token_program.to_account_info(),
+            amount,
+        )?;
+
+        let token_key = self.token_mint.key();
+        let vault_signer_seeds: &[&[&[u8]]] = &[&BondingCurve::get_sol_vault_signer(
+            &token_key,
+            &bump_sol_vault,
+        )];
+        sol_transfer_from_vault(
+            &self.sol_vault.to_account_info(),
+            &self.holder.to_account_info(),
+            &self.system_program.to_account_info(),
+            vault_signer_seeds,
+            sol_amount,
+        )?;
+
+        bonding_curve.refunded_tokens = refunded_tokens;
+        bonding_curve.real_sol_reserve = bonding_curve
+            .real_sol_reserve
+            .checked_sub(sol_amount)
+            .ok_or(PumpFunError::InvalidReserves)?;
+
+        let refund_claim = &mut self.refund_claim;
+        refund_claim.owner = self.holder.key();
+        refund_claim.token_mint = self.token_mint.key();
+        refund_claim.bump = bump_refund_claim;
+        refund_claim.tokens_burned = refund_claim
+            .tokens_burned
+            .checked_add(amount)
+            .ok_or(PumpFunError::MathOverflow)?;
+        refund_claim.sol_claimed = refund_claim
+            .sol_claimed
+            .checked_add(sol_amount)
+            .ok_or(PumpFunError::MathOverflow)?;
+
+        emit!(RefundClaimed {
+            token_mint: self.token_mint.key(),
+            owner: self.holder.key(),
+            tokens_burned: amount,
+            sol_amount,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{
+    errors::PumpFunError,
+    states::{Config, Referrer},
+};
+
+use anchor_lang::{prelude::*, system_program};
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+
+    #[account(seeds = [Config::SEED_PREFIX.as_bytes()], bump)]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + Referrer::LEN,
+        seeds = [Referrer::SEED_PREFIX.as_bytes(), &owner.key().to_bytes()],
+        bump
+    )]
+    referrer: Box<Account<'info, Referrer>>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> RegisterReferrer<'info> {
+    pub fn process(&mut self, share_bps: u16, bump_referrer: u8) -> Result<()> {
+        require!(
+            share_bps <= self.global_config.max_referral_share_bps,
+            PumpFunError::IncorrectValueRange
+        );
+
+        // Registering again only updates the share, the running totals carry over
+        let referrer = &mut self.referrer;
+        referrer.owner = self.owner.key();
+        referrer.share_bps = share_bps;
+        referrer.bump = bump_referrer;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use crate::{
+    errors::PumpFunError,
+    states::{BondingCurve, Config},
+    utils::CurvePauseUpdated,
+};
+
+use anchor_lang::prelude::*;
+
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+pub struct SetCurvePause<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ PumpFunError::UnauthorizedAddress
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(mut, seeds = [BondingCurve::SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump)]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    token_mint: Box<Account<'info, Mint>>,
+}
+
+impl<'info> SetCurvePause<'info> {
+    pub fn process(&mut self, paused: bool) -> Result<()> {
+        self.bonding_curve.is_paused = paused;
+
+        emit!(CurvePauseUpdated {
+            token_mint: self.token_mint.key(),
+            paused,
+        });
+
+        Ok(())
+    }
+}
@@ -1,13 +1,13 @@
 use crate::{
     errors::PumpFunError,
-    states::{BondingCurve, Config},
+    states::{BondingCurve, Config, CurveTrade, FeeStats, Referrer, TradeTracker},
 };
 
 use anchor_lang::{prelude::*, system_program};
 
 use anchor_spl::{
     associated_token::{self, AssociatedToken},
-    token::{self, Mint, Token, TokenAccount},
+    token::{self, spl_token::native_mint, Mint, Token, TokenAccount},
 };
 
 #[derive(Accounts)]
@@ -18,13 +18,21 @@ pub struct Swap<'info> {
     #[account(seeds = [Config::SEED_PREFIX.as_bytes()], bump)]
     global_config: Box<Account<'info, Config>>,
 
-    /// CHECK: This account is verified by through the global config constraint
-    #[account(mut, constraint = global_config.fee_recipient == fee_recipient.key() @PumpFunError::IncorrectFeeRecipient)]
-    fee_recipient: AccountInfo<'info>,
+    #[account(mut, seeds = [BondingCurve::CREATOR_VAULT_SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump)]
+    creator_vault: SystemAccount<'info>,
+
+    #[account(mut, seeds = [FeeStats::SEED_PREFIX.as_bytes()], bump)]
+    fee_stats: Box<Account<'info, FeeStats>>,
+
+    #[account(mut, seeds = [FeeStats::VAULT_SEED_PREFIX.as_bytes()], bump)]
+    fee_vault: SystemAccount<'info>,
 
     #[account(mut, seeds = [BondingCurve::SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump)]
     bonding_curve: Box<Account<'info, BondingCurve>>,
 
+    #[account(mut, seeds = [BondingCurve::SOL_VAULT_SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump)]
+    sol_vault: SystemAccount<'info>,
+
     token_mint: Box<Account<'info, Mint>>,
 
     #[account(mut, associated_token::mint = token_mint, associated_token::authority = bonding_curve)]
@@ -33,6 +41,48 @@ pub struct Swap<'info> {
     #[account(init_if_needed, payer = user, associated_token::mint = token_mint, associated_token::authority = user)]
     user_token_account: Box<Account<'info, TokenAccount>>,
 
+    // Optional WSOL path, buys pull from and sells pay out to user_wsol_account
+    #[account(address = native_mint::ID)]
+    wsol_mint: Option<Box<Account<'info, Mint>>>,
+
+    #[account(mut, token::mint = wsol_mint, token::authority = user)]
+    user_wsol_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    // Short lived account the buy unwraps through, closed onto the curve in the same instruction.
+    // Sells pay out straight into user_wsol_account and must leave it out
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [BondingCurve::WSOL_SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()],
+        bump,
+        token::mint = wsol_mint,
+        token::authority = bonding_curve,
+    )]
+    curve_wsol_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    // Optional frontend referrer, earns its share of the trade fee
+    #[account(
+        mut,
+        seeds = [Referrer::SEED_PREFIX.as_bytes(), &referrer.owner.to_bytes()],
+        bump = referrer.bump,
+        constraint = referrer.owner != user.key() @ PumpFunError::SelfReferral
+    )]
+    referrer: Option<Box<Account<'info, Referrer>>>,
+
+    // Last buy slot of this user on this curve, sells inside the penalty window pay extra
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + TradeTracker::LEN,
+        seeds = [
+            TradeTracker::SEED_PREFIX.as_bytes(),
+            &token_mint.key().to_bytes(),
+            &user.key().to_bytes(),
+        ],
+        bump
+    )]
+    trade_tracker: Box<Account<'info, TradeTracker>>,
+
     #[account(address = token::ID)]
     token_program: Program<'info, Token>,
     #[account(address = associated_token::ID)]
@@ -47,7 +97,12 @@ impl<'info> Swap<'info> {
         amount: u64,
         direction: u8,
         min_out: u64,
+        max_price_impact_bps: Option<u16>,
         bump_bonding_curve: u8,
+        bump_sol_vault: u8,
+        bump_trade_tracker: u8,
+        bump_fee_vault: u8,
+        split_recipients: &[AccountInfo<'info>],
     ) -> Result<()> {
         let bonding_curve = &mut self.bonding_curve;
         require!(
@@ -55,42 +110,81 @@ impl<'info> Swap<'info> {
             PumpFunError::CurveLimitReached
         );
 
-        let curve_pda = &mut bonding_curve.to_account_info();
+        let slot = Clock::get()?.slot;
+        let trade_tracker = &mut self.trade_tracker;
+        trade_tracker.track(self.user.key(), self.token_mint.key(), bump_trade_tracker);
+
+        // Whatever this trade adds to the fee vault is its protocol fee
+        let fee_vault_before = self.fee_vault.lamports();
+
         let global_config: &Box<Account<'info, Config>> = &self.global_config;
 
+        let user_wsol = self.user_wsol_account.as_ref().map(|a| a.to_account_info());
+        let curve_wsol = self.curve_wsol_account.as_ref().map(|a| a.to_account_info());
+
+        let trade = CurveTrade {
+            token_mint: &self.token_mint,
+            user: &self.user.to_account_info(),
+            user_signer_seeds: &[],
+            user_ata: &self.user_token_account.to_account_info(),
+            curve_pda: &bonding_curve.to_account_info(),
+            curve_ata: &self.curve_token_account.to_account_info(),
+            sol_vault: &self.sol_vault.to_account_info(),
+            creator_vault: &self.creator_vault.to_account_info(),
+            fee_vault: &self.fee_vault.to_account_info(),
+            fee_stats: &mut self.fee_stats,
+            referrer: self.referrer.as_deref_mut(),
+            system_program: &self.system_program.to_account_info(),
+            token_program: &self.token_program.to_account_info(),
+            curve_bump: bump_bonding_curve,
+            sol_vault_bump: bump_sol_vault,
+            options: global_config.trade_options(max_price_impact_bps),
+        };
+
         if direction == 0 {
-            bonding_curve.buy(
-                &self.token_mint,
-                global_config.curve_limit,
-                &self.user,
-                curve_pda,
-                &mut self.fee_recipient,
-                &mut self.user_token_account.to_account_info(),
-                &mut self.curve_token_account.to_account_info(),
-                amount,
-                min_out,
-                global_config.buy_fee_percentage,
-                bump_bonding_curve,
-                &self.system_program.to_account_info(),
-                &self.token_program.to_account_info(),
-            )?;
+            let wsol_accounts = match (&user_wsol, &curve_wsol) {
+                (Some(user_wsol), Some(curve_wsol)) => Some((user_wsol, curve_wsol)),
+                (None, None) => None,
+                _ => return err!(PumpFunError::MissingWsolAccount),
+            };
+
+            bonding_curve.buy(trade, amount, min_out, wsol_accounts)?;
+
+            trade_tracker.last_buy_slot = slot;
         } else if direction == 1 {
+            require!(
+                curve_wsol.is_none(),
+                PumpFunError::UnexpectedWsolAccount
+            );
+
             //  sell - swap token for sol
             bonding_curve.sell(
-                &self.token_mint,
-                &self.user,
-                curve_pda,
-                &mut self.user_token_account.to_account_info(),
-                &mut self.fee_recipient,
-                &mut self.curve_token_account.to_account_info(),
+                trade,
                 amount,
                 min_out,
-                global_config.sell_fee_percentage,
-                bump_bonding_curve,
-                &self.system_program.to_account_info(),
-                &self.token_program.to_account_info(),
+                global_config.sell_penalty(trade_tracker.last_buy_slot, slot),
+                user_wsol.as_ref(),
             )?;
         }
+
+        // Forward this trade's protocol fee to the split wallets when they are passed as remaining
+        // accounts, otherwise it stays in the vault until a withdrawal splits it
+        if global_config.fee_split_per_trade && !split_recipients.is_empty() {
+            let accrued = self.fee_vault.lamports() - fee_vault_before;
+            if accrued > 0 {
+                let vault_seeds = FeeStats::get_vault_signer(&bump_fee_vault);
+                FeeStats::distribute(
+                    &global_config.fee_split,
+                    split_recipients,
+                    &self.fee_vault.to_account_info(),
+                    &self.system_program.to_account_info(),
+                    &[&vault_seeds[..]],
+                    accrued,
+                )?;
+                self.fee_stats.record_distribution(accrued)?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,73 @@
+use crate::{
+    errors::PumpFunError,
+    states::{Config, FeeStats},
+    utils::FeesWithdrawn,
+};
+
+use anchor_lang::{prelude::*, system_program};
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    fee_manager: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+        constraint = global_config.can_withdraw_fees(&fee_manager.key()) @ PumpFunError::UnauthorizedAddress
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(mut, seeds = [FeeStats::SEED_PREFIX.as_bytes()], bump)]
+    fee_stats: Box<Account<'info, FeeStats>>,
+
+    #[account(mut, seeds = [FeeStats::VAULT_SEED_PREFIX.as_bytes()], bump)]
+    fee_vault: SystemAccount<'info>,
+
+    /// CHECK: This account is verified by through the global config constraint, unused once a fee split is set
+    #[account(mut, constraint = global_config.fee_recipient == fee_recipient.key() @PumpFunError::IncorrectFeeRecipient)]
+    fee_recipient: AccountInfo<'info>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> WithdrawFees<'info> {
+    pub fn process(
+        &mut self,
+        amount: u64,
+        bump_fee_vault: u8,
+        split_recipients: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(amount > 0, PumpFunError::IncorrectValueRange);
+
+        let vault_seeds = FeeStats::get_vault_signer(&bump_fee_vault);
+        let vault_signer_seeds = &[&vault_seeds[..]];
+
+        // Split wallets come in as remaining accounts, the fee recipient stands alone otherwise
+        let fee_recipient = [self.fee_recipient.clone()];
+        let recipients = if self.global_config.fee_split.is_empty() {
+            &fee_recipient[..]
+        } else {
+            split_recipients
+        };
+
+        // The vault keeps its rent, only accrued fees can leave
+        FeeStats::distribute(
+            &self.global_config.fee_split_recipients(),
+            recipients,
+            &self.fee_vault.to_account_info(),
+            &self.system_program.to_account_info(),
+            vault_signer_seeds,
+            amount,
+        )?;
+        self.fee_stats.record_withdrawal(amount)?;
+
+        emit!(FeesWithdrawn {
+            fee_manager: self.fee_manager.key(),
+            amount,
+            total_withdrawn: self.fee_stats.withdrawn,
+        });
+
+        Ok(())
+    }
+}
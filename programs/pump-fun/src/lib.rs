@@ -20,17 +20,198 @@ pub mod pump_fun {
         ctx.accounts.process(new_config)
     }
 
-    pub fn launch(ctx: Context<Launch>, name: String, symbol: String, uri: String) -> Result<()> {
+    pub fn launch(
+        ctx: Context<Launch>,
+        name: String,
+        symbol: String,
+        uri: String,
+        creator_fee_bps: u16,
+    ) -> Result<()> {
         ctx.accounts
-            .process(name, symbol, uri, ctx.bumps.global_config)
+            .process(name, symbol, uri, creator_fee_bps, ctx.bumps.global_config)
     }
 
-    pub fn swap(ctx: Context<Swap>, amount: u64, direction: u8, min_out: u64) -> Result<()> {
-        ctx.accounts
-            .process(amount, direction, min_out, ctx.bumps.bonding_curve)
+    pub fn swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Swap<'info>>,
+        amount: u64,
+        direction: u8,
+        min_out: u64,
+        max_price_impact_bps: Option<u16>,
+    ) -> Result<()> {
+        ctx.accounts.process(
+            amount,
+            direction,
+            min_out,
+            max_price_impact_bps,
+            ctx.bumps.bonding_curve,
+            ctx.bumps.sol_vault,
+            ctx.bumps.trade_tracker,
+            ctx.bumps.fee_vault,
+            ctx.remaining_accounts,
+        )
     }
 
     pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
         Migrate::process(ctx)
     }
+
+    pub fn preview_migration(ctx: Context<PreviewMigration>) -> Result<MigrationPreview> {
+        PreviewMigration::process(ctx)
+    }
+
+    pub fn migrate_cpmm(ctx: Context<MigrateCpmm>) -> Result<()> {
+        MigrateCpmm::process(ctx)
+    }
+
+    pub fn migrate_amm(ctx: Context<MigrateAmm>) -> Result<()> {
+        MigrateAmm::process(ctx)
+    }
+
+    pub fn migrate_amm_v4(ctx: Context<MigrateAmmV4>) -> Result<()> {
+        MigrateAmmV4::process(ctx)
+    }
+
+    pub fn amm_swap(ctx: Context<AmmSwap>, amount: u64, direction: u8, min_out: u64) -> Result<()> {
+        ctx.accounts.process(amount, direction, min_out)
+    }
+
+    pub fn place_order(
+        ctx: Context<PlaceOrder>,
+        order_id: u64,
+        direction: u8,
+        amount: u64,
+        limit_price: u64,
+        min_out: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        ctx.accounts.process(
+            order_id,
+            direction,
+            amount,
+            limit_price,
+            min_out,
+            expires_at,
+            ctx.bumps.limit_order,
+        )
+    }
+
+    pub fn fill_order(ctx: Context<FillOrder>) -> Result<()> {
+        ctx.accounts.process(
+            ctx.bumps.bonding_curve,
+            ctx.bumps.sol_vault,
+            ctx.bumps.trade_tracker,
+        )
+    }
+
+    pub fn cancel_order(ctx: Context<CancelOrder>) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn create_dca(
+        ctx: Context<CreateDca>,
+        dca_id: u64,
+        installment_amount: u64,
+        installments: u64,
+        interval: i64,
+        min_out_per_installment: u64,
+    ) -> Result<()> {
+        ctx.accounts.process(
+            dca_id,
+            installment_amount,
+            installments,
+            interval,
+            min_out_per_installment,
+            ctx.bumps.dca_order,
+        )
+    }
+
+    pub fn execute_dca(ctx: Context<ExecuteDca>) -> Result<()> {
+        ctx.accounts.process(
+            ctx.bumps.bonding_curve,
+            ctx.bumps.sol_vault,
+            ctx.bumps.trade_tracker,
+        )
+    }
+
+    pub fn cancel_dca(ctx: Context<CancelDca>) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn place_trigger_order(
+        ctx: Context<PlaceTriggerOrder>,
+        order_id: u64,
+        amount: u64,
+        stop_price: u64,
+        take_profit_price: u64,
+        min_out: u64,
+    ) -> Result<()> {
+        ctx.accounts.process(
+            order_id,
+            amount,
+            stop_price,
+            take_profit_price,
+            min_out,
+            ctx.bumps.trigger_order,
+        )
+    }
+
+    pub fn execute_trigger_order(ctx: Context<ExecuteTriggerOrder>) -> Result<()> {
+        ctx.accounts.process(
+            ctx.bumps.bonding_curve,
+            ctx.bumps.sol_vault,
+            ctx.bumps.trade_tracker,
+        )
+    }
+
+    pub fn cancel_trigger_order(ctx: Context<CancelTriggerOrder>) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn refund(ctx: Context<Refund>, amount: u64) -> Result<()> {
+        ctx.accounts
+            .process(amount, ctx.bumps.sol_vault, ctx.bumps.refund_claim)
+    }
+
+    pub fn finalize(ctx: Context<Finalize>) -> Result<()> {
+        ctx.accounts
+            .process(ctx.bumps.bonding_curve, ctx.bumps.sol_vault)
+    }
+
+    pub fn collect_pool_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CollectPoolFees<'info>>,
+    ) -> Result<()> {
+        CollectPoolFees::process(ctx)
+    }
+
+    pub fn audit_curve(ctx: Context<AuditCurve>) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn set_curve_pause(ctx: Context<SetCurvePause>, paused: bool) -> Result<()> {
+        ctx.accounts.process(paused)
+    }
+
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+        ctx.accounts.process(ctx.bumps.creator_vault)
+    }
+
+    pub fn register_referrer(ctx: Context<RegisterReferrer>, share_bps: u16) -> Result<()> {
+        ctx.accounts.process(share_bps, ctx.bumps.referrer)
+    }
+
+    pub fn claim_referral_fees(ctx: Context<ClaimReferralFees>) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn withdraw_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawFees<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .process(amount, ctx.bumps.fee_vault, ctx.remaining_accounts)
+    }
+
+    pub fn close_trade_tracker(ctx: Context<CloseTradeTracker>) -> Result<()> {
+        ctx.accounts.process()
+    }
 }
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::BPS_DENOMINATOR, errors::PumpFunError};
+
+// Minimal constant product pool curves can migrate into instead of an external DEX
+#[account]
+pub struct AmmPool {
+    pub token_mint: Pubkey,
+
+    // Reserves, SOL sits in the pool's SOL vault on top of its rent and tokens in its token account
+    pub sol_reserve: u64,
+    pub token_reserve: u64,
+
+    // Liquidity seeded at migration, locked for good since no LP tokens are issued
+    pub locked_liquidity: u128,
+
+    // Swap fee kept in the pool
+    pub fee_bps: u16,
+
+    pub bump: u8,
+    pub sol_vault_bump: u8,
+
+    // Reserved field for padding
+    pub reserved: [u8; 8],
+}
+
+impl AmmPool {
+    pub const SEED_PREFIX: &'static str = "amm_pool";
+    pub const SOL_VAULT_SEED_PREFIX: &'static str = "amm_sol_vault";
+    pub const LEN: usize = 32 + 8 + 8 + 16 + 2 + 1 + 1 + 8;
+
+    // get signer for the pool's SOL vault PDA
+    pub fn get_sol_vault_signer<'a>(mint: &'a Pubkey, bump: &'a u8) -> [&'a [u8]; 3] {
+        [
+            Self::SOL_VAULT_SEED_PREFIX.as_bytes(),
+            mint.as_ref(),
+            std::slice::from_ref(bump),
+        ]
+    }
+
+    // get signer for the pool PDA
+    pub fn get_signer<'a>(mint: &'a Pubkey, bump: &'a u8) -> [&'a [u8]; 3] {
+        [
+            Self::SEED_PREFIX.as_bytes(),
+            mint.as_ref(),
+            std::slice::from_ref(bump),
+        ]
+    }
+
+    // x * y = k output for amount_in, fee taken on the way in
+    pub fn calculate_amount_out(&self, amount_in: u64, direction: u8) -> Result<(u64, u64)> {
+        let (reserve_in, reserve_out) = if direction == 0 {
+            (self.sol_reserve, self.token_reserve)
+        } else {
+            (self.token_reserve, self.sol_reserve)
+        };
+
+        let fee_amount = (amount_in as u128 * self.fee_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+        let amount_in_after_fee = (amount_in - fee_amount) as u128;
+
+        let denominator = (reserve_in as u128)
+            .checked_add(amount_in_after_fee)
+            .ok_or(PumpFunError::MathOverflow)?;
+        require!(denominator > 0, PumpFunError::DivisionByZero);
+
+        let amount_out = (reserve_out as u128)
+            .checked_mul(amount_in_after_fee)
+            .ok_or(PumpFunError::MathOverflow)?
+            / denominator;
+
+        Ok((amount_out as u64, fee_amount))
+    }
+}
@@ -1,14 +1,60 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::program::set_return_data};
 use anchor_spl::token::Mint;
 
+use super::{
+    BreakerSettings, FeeStats, GraduatedCurve, MigrationTarget, Referrer, SellPenalty, TradeOptions,
+};
 use crate::{
+    constants::{BPS_DENOMINATOR, PRICE_PRECISION},
     errors::PumpFunError,
     utils::{
-        sol_transfer_from_user, sol_transfer_with_signer, token_transfer_user,
-        token_transfer_with_signer, CurveCompleted, TokenPurchased, TokenSold,
+        price_deviation_bps, sol_transfer_from_program_account, sol_transfer_from_user,
+        sol_transfer_from_vault, sync_native, token_close_with_signer, token_transfer_with_signer,
+        CircuitBreakerTripped, CurveCompleted, TokenPurchased, TokenSold,
     },
 };
 
+// Side of a curve trade, kept per direction by the circuit breaker
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TradeDirection {
+    Buy,
+    Sell,
+}
+
+// Accounts and options of a curve trade, built once by the instruction making it
+pub struct CurveTrade<'a, 'info> {
+    pub token_mint: &'a Account<'info, Mint>,
+    pub user: &'a AccountInfo<'info>, // trader, a signer or an order escrow PDA
+    pub user_signer_seeds: &'a [&'a [&'a [u8]]], // empty unless the trader is an order escrow PDA
+    pub user_ata: &'a AccountInfo<'info>,
+    pub curve_pda: &'a AccountInfo<'info>,
+    pub curve_ata: &'a AccountInfo<'info>,
+    pub sol_vault: &'a AccountInfo<'info>,
+    pub creator_vault: &'a AccountInfo<'info>,
+    pub fee_vault: &'a AccountInfo<'info>,
+    pub fee_stats: &'a mut FeeStats,
+    pub referrer: Option<&'a mut Account<'info, Referrer>>, // referrer routing this trade, if any
+    pub system_program: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub curve_bump: u8,
+    pub sol_vault_bump: u8,
+    pub options: TradeOptions,
+}
+
+// Result of a buy or sell, written to return data for programs composing with swap
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct SwapResult {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+
+    // Curve reserves after the trade
+    pub virtual_sol_reserve: u64,
+    pub virtual_token_reserve: u64,
+    pub real_sol_reserve: u64,
+    pub real_token_reserve: u64,
+}
+
 #[account]
 pub struct BondingCurve {
     //Virtual reserves on the curve
@@ -28,13 +74,52 @@ pub struct BondingCurve {
     // New field to track if funds are migrated to Raydium
     pub is_migrated: bool,
 
+    // Where the curve migrates to, fixed at launch
+    pub migration_target: MigrationTarget,
+
+    // Pool and LP mint (the position NFT on CLMM) held by the position authority after migration
+    pub pool: Pubkey,
+    pub lp_mint: Pubkey,
+
+    // Unix time the curve has to complete by, 0 when it never expires
+    pub graduation_deadline: i64,
+
+    // Set once the deadline passed without completing, trading stops and holders redeem
+    pub is_refunding: bool,
+
+    // Real SOL and circulating tokens when refunds opened, every redemption is priced off these
+    pub refund_sol_reserve: u64,
+    pub refund_token_supply: u64,
+
+    // Tokens redeemed so far, never above refund_token_supply
+    pub refunded_tokens: u64,
+
+    // Wallet that launched the token, earns its share of the pool fees after migration
+    pub creator: Pubkey,
+
+    // Trading halted by a failed audit until the authority lifts it
+    pub is_paused: bool,
+
+    // Circuit breaker window, the price and slot it opened at and the direction it blocks
+    pub reference_price: u64,
+    pub reference_slot: u64,
+    pub breaker_tripped_direction: Option<TradeDirection>,
+
+    // Creator's cut of every trade in basis points, accrued into the creator vault
+    pub creator_fee_bps: u16,
+    pub creator_fees: u64,
+
     // Reserved field for padding
     pub reserved: [u8; 8],
 }
 
 impl<'info> BondingCurve {
     pub const SEED_PREFIX: &'static str = "bonding_curve";
-    pub const LEN: usize = 8 * 5 + 1 + 1 + 8;
+    pub const SOL_VAULT_SEED_PREFIX: &'static str = "curve_sol_vault";
+    pub const WSOL_SEED_PREFIX: &'static str = "curve_wsol";
+    pub const POSITION_AUTHORITY_SEED_PREFIX: &'static str = "position_authority";
+    pub const CREATOR_VAULT_SEED_PREFIX: &'static str = "creator_vault";
+    pub const LEN: usize = 8 * 5 + 1 + 1 + 1 + 32 + 32 + 8 + 1 + 8 * 3 + 32 + 1 + 8 * 2 + 2 + 2 + 8 + 8;
 
     // get signer for bonding curve PDA
     pub fn get_signer<'a>(mint: &'a Pubkey, bump: &'a u8) -> [&'a [u8]; 3] {
@@ -45,6 +130,33 @@ impl<'info> BondingCurve {
         ]
     }
 
+    // get signer for the data-less PDA holding the curve's SOL
+    pub fn get_sol_vault_signer<'a>(mint: &'a Pubkey, bump: &'a u8) -> [&'a [u8]; 3] {
+        [
+            Self::SOL_VAULT_SEED_PREFIX.as_bytes(),
+            mint.as_ref(),
+            std::slice::from_ref(bump),
+        ]
+    }
+
+    // get signer for the data-less PDA holding the creator's accrued fees
+    pub fn get_creator_vault_signer<'a>(mint: &'a Pubkey, bump: &'a u8) -> [&'a [u8]; 3] {
+        [
+            Self::CREATOR_VAULT_SEED_PREFIX.as_bytes(),
+            mint.as_ref(),
+            std::slice::from_ref(bump),
+        ]
+    }
+
+    // get signer for the data-less PDA that provides and holds the migrated liquidity
+    pub fn get_position_authority_signer<'a>(mint: &'a Pubkey, bump: &'a u8) -> [&'a [u8]; 3] {
+        [
+            Self::POSITION_AUTHORITY_SEED_PREFIX.as_bytes(),
+            mint.as_ref(),
+            std::slice::from_ref(bump),
+        ]
+    }
+
     //Update reserves
     pub fn update_reserves(&mut self, reserve_lamport: u64, reserve_token: u64) -> Result<bool> {
         self.virtual_sol_reserve = reserve_lamport;
@@ -53,57 +165,264 @@ impl<'info> BondingCurve {
         Ok(true)
     }
 
-    //Calculate adjusted amount out and fee amount
+    //Deadline passed before the curve completed
+    pub fn is_past_deadline(&self, now: i64) -> bool {
+        self.graduation_deadline > 0 && now >= self.graduation_deadline && !self.is_completed
+    }
+
+    //Curve at this PDA can never trade again, a finalized curve's tombstone included
+    pub fn stopped_trading(curve_pda: &AccountInfo, now: i64) -> Result<bool> {
+        let data = curve_pda.try_borrow_data()?;
+        match BondingCurve::try_deserialize(&mut &data[..]) {
+            Ok(bonding_curve) => Ok(bonding_curve.is_completed
+                || bonding_curve.is_migrated
+                || bonding_curve.is_past_deadline(now)),
+            Err(_) => GraduatedCurve::try_deserialize(&mut &data[..]).map(|_| true),
+        }
+    }
+
+    //Trading stops for good once the curve fails to graduate in time
+    pub fn check_tradable(&self) -> Result<()> {
+        require!(!self.is_paused, PumpFunError::CurvePaused);
+        require!(
+            !self.is_refunding && !self.is_past_deadline(Clock::get()?.unix_timestamp),
+            PumpFunError::CurveRefunding
+        );
+
+        Ok(())
+    }
+
+    //SOL owed for burning amount tokens, pro-rata to the reserve when refunds opened
+    pub fn refund_amount(&self, amount: u64) -> Result<u64> {
+        require!(self.refund_token_supply > 0, PumpFunError::DivisionByZero);
+
+        let sol_out = (amount as u128)
+            .checked_mul(self.refund_sol_reserve as u128)
+            .ok_or(PumpFunError::MathOverflow)?
+            / self.refund_token_supply as u128;
+
+        u64::try_from(sol_out).map_err(|_| error!(PumpFunError::MathOverflow))
+    }
+
+    //SOL vault covers the real SOL reserve on top of its rent, anything above it is a donation
+    pub fn check_sol_vault(&self, sol_vault: &AccountInfo) -> Result<()> {
+        let expected = self
+            .real_sol_reserve
+            .checked_add(Rent::get()?.minimum_balance(0))
+            .ok_or(PumpFunError::MathOverflow)?;
+        require!(
+            sol_vault.lamports() >= expected,
+            PumpFunError::SolVaultMismatch
+        );
+
+        Ok(())
+    }
+
+    //Publish the trade result through return data
+    pub fn set_swap_result(&self, amount_in: u64, amount_out: u64, fee_amount: u64) -> Result<()> {
+        let result = SwapResult {
+            amount_in,
+            amount_out,
+            fee_amount,
+            virtual_sol_reserve: self.virtual_sol_reserve,
+            virtual_token_reserve: self.virtual_token_reserve,
+            real_sol_reserve: self.real_sol_reserve,
+            real_token_reserve: self.real_token_reserve,
+        };
+        set_return_data(&result.try_to_vec()?);
+
+        Ok(())
+    }
+
+    //Spot price from virtual reserves, lamports per token unit scaled by PRICE_PRECISION
+    pub fn spot_price(&self) -> Result<u64> {
+        require!(self.virtual_token_reserve > 0, PumpFunError::DivisionByZero);
+
+        let price = (self.virtual_sol_reserve as u128)
+            .checked_mul(PRICE_PRECISION)
+            .ok_or(PumpFunError::MathOverflow)?
+            / self.virtual_token_reserve as u128;
+
+        u64::try_from(price).map_err(|_| error!(PumpFunError::MathOverflow))
+    }
+
+    //Fail when the spot price moved further than max_price_impact_bps since price_before
+    pub fn check_price_impact(&self, price_before: u64, max_price_impact_bps: u16) -> Result<()> {
+        if max_price_impact_bps == 0 {
+            return Ok(());
+        }
+
+        let impact_bps = price_deviation_bps(price_before, self.spot_price()?)?;
+        require!(
+            impact_bps <= max_price_impact_bps as u64,
+            PumpFunError::PriceImpactExceeded
+        );
+
+        Ok(())
+    }
+
+    //Open a new breaker window once the last one ran out, then refuse a tripped direction
+    pub fn check_circuit_breaker(
+        &mut self,
+        direction: TradeDirection,
+        breaker: BreakerSettings,
+        price_before: u64,
+    ) -> Result<()> {
+        if breaker.threshold_bps == 0 {
+            return Ok(());
+        }
+
+        let slot = Clock::get()?.slot;
+        if self.reference_price == 0
+            || slot >= self.reference_slot.saturating_add(breaker.window_slots)
+        {
+            self.reference_price = price_before;
+            self.reference_slot = slot;
+            self.breaker_tripped_direction = None;
+        }
+
+        require!(
+            self.breaker_tripped_direction != Some(direction),
+            PumpFunError::CircuitBreakerTripped
+        );
+
+        Ok(())
+    }
+
+    //Trip the breaker for this direction once the window's move passes the threshold
+    pub fn update_circuit_breaker(
+        &mut self,
+        token_mint: Pubkey,
+        direction: TradeDirection,
+        breaker: BreakerSettings,
+    ) -> Result<()> {
+        if breaker.threshold_bps == 0 {
+            return Ok(());
+        }
+
+        let price = self.spot_price()?;
+        let moved_with_trade = match direction {
+            TradeDirection::Buy => price > self.reference_price,
+            TradeDirection::Sell => price < self.reference_price,
+        };
+
+        if moved_with_trade
+            && price_deviation_bps(self.reference_price, price)? > breaker.threshold_bps as u64
+        {
+            self.breaker_tripped_direction = Some(direction);
+            emit!(CircuitBreakerTripped {
+                token_mint,
+                direction,
+                reference_price: self.reference_price,
+                price,
+                reference_slot: self.reference_slot,
+                slot: Clock::get()?.slot,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn record_creator_fee(&mut self, amount: u64) -> Result<()> {
+        self.creator_fees = self
+            .creator_fees
+            .checked_add(amount)
+            .ok_or(PumpFunError::MathOverflow)?;
+        Ok(())
+    }
+
+    // Share of amount in basis points, rounded down
+    fn bps_share(amount: u64, bps: u16) -> Result<u64> {
+        Ok((amount as u128)
+            .checked_mul(bps as u128)
+            .ok_or(PumpFunError::MathOverflow)?
+            .checked_div(BPS_DENOMINATOR as u128)
+            .ok_or(PumpFunError::DivisionByZero)? as u64)
+    }
+
+    //Calculate adjusted amount out and fee amount, the fee is in lamports either way:
+    //taken off the SOL paid in on buys, and off the gross SOL paid out on sells
     pub fn calculate_amount_out(
         &mut self,
         amount_in: u64,
         direction: u8,
         fee_percentage: f64,
     ) -> Result<(u64, u64)> {
-        let fee_amount = (amount_in as f64 * fee_percentage / 100.0) as u64;
-        let amount_after_fee = amount_in
-            .checked_sub(fee_amount)
-            .ok_or(PumpFunError::InsufficientFunds)?;
+        let lamport_fee = |lamports: u64| (lamports as f64 * fee_percentage / 100.0) as u64;
 
         let virtual_sol = self.virtual_sol_reserve as f64;
         let virtual_token = self.virtual_token_reserve as f64;
-        let amount_after_fee = amount_after_fee as f64;
 
         const CRR: f64 = 0.2;
 
-        let amount_out = if direction == 0 {
+        if direction == 0 {
+            let fee_amount = lamport_fee(amount_in);
+            let amount_after_fee = amount_in
+                .checked_sub(fee_amount)
+                .ok_or(PumpFunError::InsufficientFunds)?;
+
             require!(virtual_sol > 0.0, PumpFunError::DivisionByZero);
-            let base = 1.0 + amount_after_fee / virtual_sol;
-            virtual_token * (base.powf(CRR) - 1.0)
+            let base = 1.0 + amount_after_fee as f64 / virtual_sol;
+            let amount_out = virtual_token * (base.powf(CRR) - 1.0);
+
+            Ok((amount_out.floor() as u64, fee_amount))
         } else {
             require!(virtual_token > 0.0, PumpFunError::DivisionByZero);
-            let base = 1.0 - amount_after_fee / virtual_token;
-            virtual_sol * (1.0 - base.powf(1.0 / CRR))
-        };
-
-        let final_amount = amount_out.floor() as u64;
+            let base = 1.0 - amount_in as f64 / virtual_token;
+            let amount_out = (virtual_sol * (1.0 - base.powf(1.0 / CRR))).floor() as u64;
 
-        Ok((final_amount, fee_amount))
+            Ok((amount_out, lamport_fee(amount_out)))
+        }
     }
 
     // Swap sol for tokens
     pub fn buy(
         &mut self,
-        token_mint: &Account<'info, Mint>,  // Token mint address
-        curve_limit: u64,                   // Bonding Curve Limit
-        user: &Signer<'info>,               // User address for buyer
-        curve_pda: &mut AccountInfo<'info>, // Bonding Curve PDA
-        fee_recipient: &mut AccountInfo<'info>, // Team wallet address to get fees
-        user_ata: &mut AccountInfo<'info>,  // Associated token account for user
-        curve_ata: &AccountInfo<'info>,     // Associated token account for bonding curve
-        amount_in: u64,                     // Amount of SOL to pay
-        min_amount_out: u64,                // Minimum amount of tokens to receive
-        fee_percentage: f64,                // Fee percentage for buying on the bonding curve
-        curve_bump: u8,                     // Bump for the bonding curve PDA
-        system_program: &AccountInfo<'info>, // System program
-        token_program: &AccountInfo<'info>,
+        trade: CurveTrade<'_, 'info>,
+        amount_in: u64,      // Amount of SOL to pay
+        min_amount_out: u64, // Minimum amount of tokens to receive
+        wsol_accounts: Option<(&AccountInfo<'info>, &AccountInfo<'info>)>, // User and curve WSOL accounts when paying in WSOL
     ) -> Result<bool> {
-        let (amount_out, fee_amount) = self.calculate_amount_out(amount_in, 0, fee_percentage)?;
+        let CurveTrade {
+            token_mint,
+            user,
+            user_ata,
+            curve_pda,
+            curve_ata,
+            sol_vault,
+            creator_vault,
+            fee_vault,
+            fee_stats,
+            referrer,
+            system_program,
+            token_program,
+            curve_bump,
+            sol_vault_bump,
+            options,
+            ..
+        } = trade;
+
+        self.check_tradable()?;
+
+        let price_before = self.spot_price()?;
+        self.check_circuit_breaker(TradeDirection::Buy, options.breaker, price_before)?;
+
+        // Creator's cut comes off the top, the protocol fee is taken on the rest
+        let creator_fee = Self::bps_share(amount_in, self.creator_fee_bps)?;
+        let (amount_out, fee_amount) =
+            self.calculate_amount_out(amount_in - creator_fee, 0, options.buy_fee_percentage)?;
+        let curve_amount = amount_in - creator_fee - fee_amount;
+
+        // Referrer's cut comes out of the protocol fee
+        let referral_fee = match referrer.as_ref() {
+            Some(referrer) => Self::bps_share(
+                fee_amount,
+                referrer.share_bps.min(options.max_referral_share_bps),
+            )?,
+            None => 0,
+        };
+        let protocol_fee = fee_amount - referral_fee;
+        let referrer_info = referrer.as_ref().map(|referrer| referrer.to_account_info());
 
         // Check if the amount out is greater than the minimum amount out
         require!(
@@ -111,11 +430,73 @@ impl<'info> BondingCurve {
             PumpFunError::InsufficientAmountOut
         );
 
-        // Transfer fee to the team wallet
-        sol_transfer_from_user(&user, fee_recipient, system_program, fee_amount)?;
+        let token = token_mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&BondingCurve::get_signer(&token, &curve_bump)];
+        let vault_signer_seeds: &[&[&[u8]]] =
+            &[&BondingCurve::get_sol_vault_signer(&token, &sol_vault_bump)];
+
+        if let Some((user_wsol, curve_wsol)) = wsol_accounts {
+            // Move WSOL into the curve's unwrap account and close it into the SOL vault
+            let unwrap_rent = curve_wsol.lamports();
+            token_transfer_with_signer(user_wsol, user, curve_wsol, token_program, &[], amount_in)?;
+            token_close_with_signer(
+                curve_wsol,
+                curve_pda,
+                sol_vault,
+                token_program,
+                signer_seeds,
+            )?;
+
+            // Hand the unwrap account rent back to the buyer and route the fees
+            sol_transfer_from_vault(sol_vault, user, system_program, vault_signer_seeds, unwrap_rent)?;
+            sol_transfer_from_vault(
+                sol_vault,
+                fee_vault,
+                system_program,
+                vault_signer_seeds,
+                protocol_fee,
+            )?;
+            sol_transfer_from_vault(
+                sol_vault,
+                creator_vault,
+                system_program,
+                vault_signer_seeds,
+                creator_fee,
+            )?;
+            if let Some(referrer_info) = &referrer_info {
+                sol_transfer_from_vault(
+                    sol_vault,
+                    referrer_info,
+                    system_program,
+                    vault_signer_seeds,
+                    referral_fee,
+                )?;
+            }
+        } else if user.owner == &crate::ID {
+            // Escrowed SOL sits on a program owned account
+            sol_transfer_from_program_account(user, fee_vault, protocol_fee)?;
+            sol_transfer_from_program_account(user, creator_vault, creator_fee)?;
+            if let Some(referrer_info) = &referrer_info {
+                sol_transfer_from_program_account(user, referrer_info, referral_fee)?;
+            }
+            sol_transfer_from_program_account(user, sol_vault, curve_amount)?;
+        } else {
+            // Accrue the fees into the protocol, creator and referrer accounts
+            sol_transfer_from_user(user, fee_vault, system_program, protocol_fee)?;
+            sol_transfer_from_user(user, creator_vault, system_program, creator_fee)?;
+            if let Some(referrer_info) = &referrer_info {
+                sol_transfer_from_user(user, referrer_info, system_program, referral_fee)?;
+            }
+
+            // Transfer adjusted amount to the SOL vault
+            sol_transfer_from_user(user, sol_vault, system_program, curve_amount)?;
+        }
 
-        // Transfer adjusted amount to curve
-        sol_transfer_from_user(&user, curve_pda, system_program, amount_in - fee_amount)?;
+        fee_stats.record_buy_fee(protocol_fee)?;
+        self.record_creator_fee(creator_fee)?;
+        if let Some(referrer) = referrer {
+            referrer.record_trade(amount_in, referral_fee)?;
+        }
 
         // Transfer tokens from PDA to user
         token_transfer_with_signer(
@@ -123,7 +504,7 @@ impl<'info> BondingCurve {
             curve_pda,
             user_ata,
             token_program,
-            &[&BondingCurve::get_signer(&token_mint.key(), &curve_bump)],
+            signer_seeds,
             amount_out,
         )?;
 
@@ -135,11 +516,25 @@ impl<'info> BondingCurve {
 
         let new_sol_reserves = self
             .virtual_sol_reserve
-            .checked_add(amount_in - fee_amount)
+            .checked_add(curve_amount)
             .ok_or(PumpFunError::InvalidReserves)?;
 
         //Update reserves on the curve
         self.update_reserves(new_sol_reserves, new_token_reserves)?;
+        self.check_price_impact(price_before, options.max_price_impact_bps)?;
+        self.update_circuit_breaker(token_mint.key(), TradeDirection::Buy, options.breaker)?;
+
+        self.real_token_reserve = self
+            .real_token_reserve
+            .checked_sub(amount_out)
+            .ok_or(PumpFunError::InvalidReserves)?;
+        self.real_sol_reserve = self
+            .real_sol_reserve
+            .checked_add(curve_amount)
+            .ok_or(PumpFunError::InvalidReserves)?;
+        self.check_sol_vault(sol_vault)?;
+
+        self.set_swap_result(amount_in, amount_out, fee_amount)?;
 
         emit!(TokenPurchased {
             token_mint: token_mint.key(),
@@ -147,11 +542,14 @@ impl<'info> BondingCurve {
             sol_amount: amount_in,
             token_amount: amount_out,
             fee_amount: fee_amount,
+            creator_fee,
+            referrer: referrer_info.as_ref().map(|referrer| referrer.key()),
+            referral_fee,
             price: new_sol_reserves / new_token_reserves
         });
 
         //Return true if curve reached its limit
-        if new_sol_reserves >= curve_limit {
+        if new_sol_reserves >= options.curve_limit {
             self.is_completed = true;
             emit!(CurveCompleted {
                 token_mint: token_mint.key(),
@@ -168,46 +566,131 @@ impl<'info> BondingCurve {
     // Swap tokens for sol
     pub fn sell(
         &mut self,
-        token_mint: &Account<'info, Mint>,
-        user: &Signer<'info>,
-        curve_pda: &mut AccountInfo<'info>,
-        user_ata: &mut AccountInfo<'info>,
-        fee_recipient: &mut AccountInfo<'info>,
-        curve_ata: &mut AccountInfo<'info>,
+        trade: CurveTrade<'_, 'info>,
         amount_in: u64,
         min_amount_out: u64,
-        fee_percentage: f64,
-        curve_bump: u8,
-        system_program: &AccountInfo<'info>,
-        token_program: &AccountInfo<'info>,
+        penalty: SellPenalty,                   // extra fee for selling right after buying
+        user_wsol: Option<&AccountInfo<'info>>, // pay out as WSOL into this account when set
     ) -> Result<()> {
-        let (amount_out, fee_amount) = self.calculate_amount_out(amount_in, 1, fee_percentage)?;
+        let CurveTrade {
+            token_mint,
+            user,
+            user_signer_seeds,
+            user_ata,
+            curve_ata,
+            sol_vault,
+            creator_vault,
+            fee_vault,
+            fee_stats,
+            referrer,
+            system_program,
+            token_program,
+            sol_vault_bump,
+            options,
+            ..
+        } = trade;
+
+        self.check_tradable()?;
+
+        let price_before = self.spot_price()?;
+        self.check_circuit_breaker(TradeDirection::Sell, options.breaker, price_before)?;
 
+        // Gross SOL out of the curve and the fee on it, both in lamports
+        let (amount_out, fee_amount) =
+            self.calculate_amount_out(amount_in, 1, options.sell_fee_percentage)?;
+        let proceeds = amount_out
+            .checked_sub(fee_amount)
+            .ok_or(PumpFunError::InsufficientAmountOut)?;
+
+        // Creator fee and penalty come off the seller's proceeds on top of the regular fee
+        let creator_fee = Self::bps_share(proceeds, self.creator_fee_bps)?;
+        let penalty_amount = Self::bps_share(proceeds, penalty.penalty_bps)?;
+        let seller_amount = proceeds
+            .checked_sub(
+                creator_fee
+                    .checked_add(penalty_amount)
+                    .ok_or(PumpFunError::MathOverflow)?,
+            )
+            .ok_or(PumpFunError::InsufficientAmountOut)?;
+
+        // Slippage is checked on what actually reaches the seller
         require!(
-            amount_out >= min_amount_out,
+            seller_amount >= min_amount_out,
             PumpFunError::InsufficientAmountOut
         );
 
-        let token = token_mint.key();
-        let signer_seeds: &[&[&[u8]]] = &[&BondingCurve::get_signer(&token, &curve_bump)];
+        // Kept in the curve when redistributed to it, accrued with the fee otherwise
+        let (curve_penalty, recipient_penalty) = if penalty.to_curve {
+            (penalty_amount, 0)
+        } else {
+            (0, penalty_amount)
+        };
 
-        token_transfer_user(user_ata, curve_ata, user, token_program, amount_in)?;
+        // Referrer's cut comes out of the protocol fee, never out of the penalty
+        let referral_fee = match referrer.as_ref() {
+            Some(referrer) => Self::bps_share(
+                fee_amount,
+                referrer.share_bps.min(options.max_referral_share_bps),
+            )?,
+            None => 0,
+        };
+        let protocol_fee = fee_amount - referral_fee + recipient_penalty;
+        let referrer_info = referrer.as_ref().map(|referrer| referrer.to_account_info());
 
-        sol_transfer_with_signer(
-            curve_pda,
+        let token = token_mint.key();
+        let vault_signer_seeds: &[&[&[u8]]] =
+            &[&BondingCurve::get_sol_vault_signer(&token, &sol_vault_bump)];
+
+        token_transfer_with_signer(
+            user_ata,
             user,
+            curve_ata,
+            token_program,
+            user_signer_seeds,
+            amount_in,
+        )?;
+
+        sol_transfer_from_vault(
+            sol_vault,
+            user_wsol.unwrap_or(user),
             system_program,
-            signer_seeds,
-            amount_out - fee_amount,
+            vault_signer_seeds,
+            seller_amount,
         )?;
 
-        sol_transfer_with_signer(
-            curve_pda,
-            fee_recipient,
+        // Wrap the proceeds so the WSOL balance matches the lamports
+        if let Some(user_wsol) = user_wsol {
+            sync_native(user_wsol, token_program)?;
+        }
+
+        sol_transfer_from_vault(
+            sol_vault,
+            fee_vault,
             system_program,
-            signer_seeds,
-            fee_amount,
+            vault_signer_seeds,
+            protocol_fee,
         )?;
+        fee_stats.record_sell_fee(protocol_fee)?;
+
+        sol_transfer_from_vault(
+            sol_vault,
+            creator_vault,
+            system_program,
+            vault_signer_seeds,
+            creator_fee,
+        )?;
+        self.record_creator_fee(creator_fee)?;
+
+        if let Some(referrer) = referrer {
+            sol_transfer_from_vault(
+                sol_vault,
+                &referrer.to_account_info(),
+                system_program,
+                vault_signer_seeds,
+                referral_fee,
+            )?;
+            referrer.record_trade(amount_out, referral_fee)?;
+        }
 
         let new_token_reserves = self
             .virtual_token_reserve
@@ -216,16 +699,34 @@ impl<'info> BondingCurve {
 
         let new_sol_reserves = self
             .virtual_sol_reserve
-            .checked_sub(amount_out)
+            .checked_sub(amount_out - curve_penalty)
             .ok_or(PumpFunError::InvalidReserves)?;
 
         self.update_reserves(new_sol_reserves, new_token_reserves)?;
+        self.check_price_impact(price_before, options.max_price_impact_bps)?;
+        self.update_circuit_breaker(token_mint.key(), TradeDirection::Sell, options.breaker)?;
+
+        self.real_token_reserve = self
+            .real_token_reserve
+            .checked_add(amount_in)
+            .ok_or(PumpFunError::InvalidReserves)?;
+        self.real_sol_reserve = self
+            .real_sol_reserve
+            .checked_sub(amount_out - curve_penalty)
+            .ok_or(PumpFunError::InvalidReserves)?;
+        self.check_sol_vault(sol_vault)?;
+
+        self.set_swap_result(amount_in, amount_out, fee_amount)?;
 
         emit!(TokenSold {
             token_mint: token_mint.key(),
             sol_amount: amount_in,
             token_amount: amount_out,
             fee_amount: fee_amount,
+            creator_fee,
+            referrer: referrer_info.as_ref().map(|referrer| referrer.key()),
+            referral_fee,
+            penalty_amount,
             price: new_sol_reserves / new_token_reserves
         });
 
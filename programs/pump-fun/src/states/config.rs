@@ -1,5 +1,41 @@
 use anchor_lang::prelude::*;
 
+use super::MigrationTarget;
+use crate::{
+    constants::{BPS_DENOMINATOR, MAX_FEE_SPLIT_RECIPIENTS},
+    errors::PumpFunError,
+};
+
+// One wallet's cut of the protocol fees
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct FeeSplitRecipient {
+    pub wallet: Pubkey,
+    pub weight_bps: u16,
+}
+
+impl FeeSplitRecipient {
+    pub const LEN: usize = 32 + 2;
+
+    // Each recipient's cut of amount by weight, the last one takes the rounding dust
+    pub fn cuts(split: &[FeeSplitRecipient], amount: u64) -> Result<Vec<u64>> {
+        let mut paid = 0u64;
+        let mut cuts = Vec::with_capacity(split.len());
+        for (i, share) in split.iter().enumerate() {
+            let cut = if i + 1 == split.len() {
+                amount - paid
+            } else {
+                ((amount as u128)
+                    .checked_mul(share.weight_bps as u128)
+                    .ok_or(PumpFunError::MathOverflow)?
+                    / BPS_DENOMINATOR as u128) as u64
+            };
+            paid += cut;
+            cuts.push(cut);
+        }
+        Ok(cuts)
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
 pub struct ConfigSettings {
     // New struct for the instruction argument
@@ -20,9 +56,122 @@ pub struct ConfigSettings {
     pub sell_fee_percentage: f64,
     pub migration_fee_percentage: f64,
 
+    // lamports paid to whoever cranks an order
+    pub crank_fee: u64,
+
+    // hard cap on price impact per trade in basis points, 0 disables it
+    pub max_price_impact_bps: u16,
+
+    // allowed gap between the migration pool price and the final curve price in basis points,
+    // never below the migration fee in basis points since the fee comes off the pool's SOL side
+    pub migration_price_tolerance_bps: u16,
+
+    // where new curves migrate to once completed
+    pub migration_target: MigrationTarget,
+
+    // lamports paid out of the migration fee to whoever migrates a completed curve
+    pub migration_bounty: u64,
+
+    // only wallet allowed to migrate, the default key lets anyone migrate
+    pub migrator: Pubkey,
+
+    // seconds a new curve has to complete before holders can claim refunds, 0 disables it
+    pub graduation_window: i64,
+
+    // creator's share of the migrated pool's trading fees in basis points, the rest goes to the protocol
+    pub creator_lp_fee_bps: u16,
+
+    // allowlisted Raydium AMM config indexes migrations may create pools under
+    pub clmm_config_index: u16,
+    pub cpmm_config_index: u16,
+
+    // SOL or token shortfall at which audit_curve pauses a curve, 0 never pauses
+    pub audit_pause_threshold: u64,
+
+    // circuit breaker, price move in basis points allowed per window of slots, 0 disables it
+    pub breaker_window_slots: u64,
+    pub breaker_threshold_bps: u16,
+
+    // sells within this many slots of the seller's last buy pay an extra fee in basis points, 0 disables it
+    pub sell_penalty_window_slots: u64,
+    pub sell_penalty_bps: u16,
+    // keep the extra fee in the curve instead of sending it to the fee recipient
+    pub sell_penalty_to_curve: bool,
+
+    // wallet allowed to withdraw from the fee vault, the default key leaves it to the authority
+    pub fee_manager: Pubkey,
+
+    // lamports charged to launch a token
+    pub launch_fee: u64,
+
+    // highest creator fee in basis points a launch may set on its curve
+    pub max_creator_fee_bps: u16,
+
+    // highest share of the trade fee in basis points a referrer can earn
+    pub max_referral_share_bps: u16,
+
+    // protocol fee recipients with weights summing to 10,000, empty sends everything to fee_recipient
+    pub fee_split: Vec<FeeSplitRecipient>,
+    // distribute each swap's protocol fee when the swap passes the split wallets, not only on withdrawal
+    pub fee_split_per_trade: bool,
+
     pub reserved: [[u8; 8]; 8],
 }
 
+impl ConfigSettings {
+    // A split is either unset or a handful of distinct wallets whose weights add up to 100%
+    pub fn is_valid_fee_split(&self) -> bool {
+        if self.fee_split.is_empty() {
+            // Per trade distribution has nowhere to send fees without a split
+            return !self.fee_split_per_trade;
+        }
+
+        let total: u64 = self
+            .fee_split
+            .iter()
+            .map(|recipient| recipient.weight_bps as u64)
+            .sum();
+        let distinct = self.fee_split.iter().enumerate().all(|(i, recipient)| {
+            self.fee_split[..i]
+                .iter()
+                .all(|other| other.wallet != recipient.wallet)
+        });
+
+        self.fee_split.len() <= MAX_FEE_SPLIT_RECIPIENTS
+            && total == BPS_DENOMINATOR
+            && distinct
+            && self
+                .fee_split
+                .iter()
+                .all(|recipient| recipient.wallet != Pubkey::default() && recipient.weight_bps > 0)
+    }
+}
+
+// Circuit breaker settings handed to the curve on every trade
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BreakerSettings {
+    pub window_slots: u64,
+    pub threshold_bps: u16,
+}
+
+// Extra sell fee owed by a trader who bought within the penalty window
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SellPenalty {
+    pub penalty_bps: u16,
+    pub to_curve: bool,
+}
+
+// Config limits and fees a curve trade runs under, read once per instruction
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TradeOptions {
+    pub curve_limit: u64,
+    pub buy_fee_percentage: f64,
+    pub sell_fee_percentage: f64,
+    pub max_referral_share_bps: u16,
+    pub max_price_impact_bps: u16,
+    pub breaker: BreakerSettings,
+}
+
 #[account]
 pub struct Config {
     pub authority: Pubkey,
@@ -42,10 +191,138 @@ pub struct Config {
     pub sell_fee_percentage: f64,
     pub migration_fee_percentage: f64,
 
+    // lamports paid to whoever cranks an order
+    pub crank_fee: u64,
+
+    // hard cap on price impact per trade in basis points, 0 disables it
+    pub max_price_impact_bps: u16,
+
+    // allowed gap between the migration pool price and the final curve price in basis points,
+    // never below the migration fee in basis points since the fee comes off the pool's SOL side
+    pub migration_price_tolerance_bps: u16,
+
+    // where new curves migrate to once completed
+    pub migration_target: MigrationTarget,
+
+    // lamports paid out of the migration fee to whoever migrates a completed curve
+    pub migration_bounty: u64,
+
+    // only wallet allowed to migrate, the default key lets anyone migrate
+    pub migrator: Pubkey,
+
+    // seconds a new curve has to complete before holders can claim refunds, 0 disables it
+    pub graduation_window: i64,
+
+    // creator's share of the migrated pool's trading fees in basis points, the rest goes to the protocol
+    pub creator_lp_fee_bps: u16,
+
+    // allowlisted Raydium AMM config indexes migrations may create pools under
+    pub clmm_config_index: u16,
+    pub cpmm_config_index: u16,
+
+    // SOL or token shortfall at which audit_curve pauses a curve, 0 never pauses
+    pub audit_pause_threshold: u64,
+
+    // circuit breaker, price move in basis points allowed per window of slots, 0 disables it
+    pub breaker_window_slots: u64,
+    pub breaker_threshold_bps: u16,
+
+    // sells within this many slots of the seller's last buy pay an extra fee in basis points, 0 disables it
+    pub sell_penalty_window_slots: u64,
+    pub sell_penalty_bps: u16,
+    // keep the extra fee in the curve instead of sending it to the fee recipient
+    pub sell_penalty_to_curve: bool,
+
+    // wallet allowed to withdraw from the fee vault, the default key leaves it to the authority
+    pub fee_manager: Pubkey,
+
+    // lamports charged to launch a token
+    pub launch_fee: u64,
+
+    // highest creator fee in basis points a launch may set on its curve
+    pub max_creator_fee_bps: u16,
+
+    // highest share of the trade fee in basis points a referrer can earn
+    pub max_referral_share_bps: u16,
+
+    // protocol fee recipients with weights summing to 10,000, empty sends everything to fee_recipient
+    pub fee_split: Vec<FeeSplitRecipient>,
+    // distribute each swap's protocol fee when the swap passes the split wallets, not only on withdrawal
+    pub fee_split_per_trade: bool,
+
     pub reserved: [[u8; 8]; 8],
 }
 
 impl Config {
     pub const SEED_PREFIX: &'static str = "global_config";
-    pub const LEN: usize = 32 + (32 * 5) + 32 + 8 + (8 * 4) + (8 * 3) + 64;
+    pub const LEN: usize = 32 + (32 * 5) + 32 + 8 + (8 * 4) + (8 * 3) + 8 + 2 + 2 + 1 + 8 + 32 + 8 + 2 + 2 + 2 + 8 + 8 + 2 + 8 + 2 + 1 + 32 + 8 + 2 + 2 + 4 + FeeSplitRecipient::LEN * MAX_FEE_SPLIT_RECIPIENTS + 1 + 64;
+
+    // Tighter of the client's limit and the protocol cap, 0 on either side sets no limit of its own.
+    // A requested 0 falls back to the cap so clients can never opt out of it
+    pub fn price_impact_limit(&self, requested_bps: Option<u16>) -> u16 {
+        match (requested_bps, self.max_price_impact_bps) {
+            (None | Some(0), cap) => cap,
+            (Some(requested), 0) => requested,
+            (Some(requested), cap) => requested.min(cap),
+        }
+    }
+
+    pub fn breaker_settings(&self) -> BreakerSettings {
+        BreakerSettings {
+            window_slots: self.breaker_window_slots,
+            threshold_bps: self.breaker_threshold_bps,
+        }
+    }
+
+    pub fn trade_options(&self, requested_price_impact_bps: Option<u16>) -> TradeOptions {
+        TradeOptions {
+            curve_limit: self.curve_limit,
+            buy_fee_percentage: self.buy_fee_percentage,
+            sell_fee_percentage: self.sell_fee_percentage,
+            max_referral_share_bps: self.max_referral_share_bps,
+            max_price_impact_bps: self.price_impact_limit(requested_price_impact_bps),
+            breaker: self.breaker_settings(),
+        }
+    }
+
+    // Penalty for a sell at slot, nothing unless the trader bought within the window
+    pub fn sell_penalty(&self, last_buy_slot: u64, slot: u64) -> SellPenalty {
+        if self.sell_penalty_bps == 0
+            || last_buy_slot == 0
+            || slot.saturating_sub(last_buy_slot) > self.sell_penalty_window_slots
+        {
+            return SellPenalty::default();
+        }
+
+        SellPenalty {
+            penalty_bps: self.sell_penalty_bps,
+            to_curve: self.sell_penalty_to_curve,
+        }
+    }
+
+    // Wallets protocol fees are paid to, the fee recipient alone when no split is set
+    pub fn fee_split_recipients(&self) -> Vec<FeeSplitRecipient> {
+        if self.fee_split.is_empty() {
+            vec![FeeSplitRecipient {
+                wallet: self.fee_recipient,
+                weight_bps: BPS_DENOMINATOR as u16,
+            }]
+        } else {
+            self.fee_split.clone()
+        }
+    }
+
+    // Fee withdrawals fall back to the authority until a fee manager is set
+    pub fn can_withdraw_fees(&self, signer: &Pubkey) -> bool {
+        if self.fee_manager.eq(&Pubkey::default()) {
+            self.authority.eq(signer)
+        } else {
+            self.fee_manager.eq(signer)
+        }
+    }
+
+    // Migration is permissionless unless a migrator role is set
+    pub fn can_migrate(&self, signer: &Pubkey) -> bool {
+        self.migrator.eq(&Pubkey::default()) || self.migrator.eq(signer)
+    }
 }
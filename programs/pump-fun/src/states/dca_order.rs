@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct DcaOrder {
+    // Wallet funding the schedule and receiving the tokens
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+
+    // Client chosen id so one owner can keep several schedules on a curve
+    pub dca_id: u64,
+
+    // SOL spent per installment and installments still to run
+    pub installment_amount: u64,
+    pub installments_left: u64,
+
+    // Seconds between installments and when the next one can run
+    pub interval: i64,
+    pub next_execution_at: i64,
+
+    // Price guard, minimum tokens out for each installment
+    pub min_out_per_installment: u64,
+
+    // Crank fee escrowed per installment, paid to whoever executes it
+    pub crank_fee: u64,
+
+    // Referrer chosen at creation, credited on every installment like a referred swap
+    pub referrer: Option<Pubkey>,
+
+    pub bump: u8,
+
+    // Reserved field for padding
+    pub reserved: [u8; 8],
+}
+
+impl DcaOrder {
+    pub const SEED_PREFIX: &'static str = "dca_order";
+    pub const LEN: usize = 32 + 32 + 8 * 4 + 8 * 2 + 8 * 2 + 33 + 1 + 8;
+
+    // get signer for DCA order PDA
+    pub fn get_signer<'a>(
+        mint: &'a Pubkey,
+        owner: &'a Pubkey,
+        dca_id: &'a [u8; 8],
+        bump: &'a u8,
+    ) -> [&'a [u8]; 5] {
+        [
+            Self::SEED_PREFIX.as_bytes(),
+            mint.as_ref(),
+            owner.as_ref(),
+            dca_id,
+            std::slice::from_ref(bump),
+        ]
+    }
+
+    // SOL still escrowed for the remaining installments, crank fees included
+    pub fn unspent_amount(&self) -> Option<u64> {
+        self.installment_amount
+            .checked_add(self.crank_fee)?
+            .checked_mul(self.installments_left)
+    }
+}
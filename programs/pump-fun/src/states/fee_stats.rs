@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+
+use super::FeeSplitRecipient;
+use crate::{
+    errors::PumpFunError,
+    utils::{sol_transfer_from_vault, FeesDistributed},
+};
+
+#[account]
+pub struct FeeStats {
+    // Cumulative lamports accrued into the fee vault, by source
+    pub buy_fees: u64,
+    pub sell_fees: u64,
+    pub migration_fees: u64,
+    pub launch_fees: u64,
+    pub finalize_sweeps: u64,
+
+    // Cumulative lamports the fee manager moved out of the vault
+    pub withdrawn: u64,
+    // Cumulative lamports forwarded to the split wallets as swaps accrued them
+    pub distributed: u64,
+
+    // Reserved field for padding
+    pub reserved: [u8; 8],
+}
+
+impl FeeStats {
+    pub const SEED_PREFIX: &'static str = "fee_stats";
+    pub const VAULT_SEED_PREFIX: &'static str = "fee_vault";
+    pub const LEN: usize = 8 * 7 + 8;
+
+    // get signer for the data-less fee vault PDA
+    pub fn get_vault_signer(bump: &u8) -> [&[u8]; 2] {
+        [
+            Self::VAULT_SEED_PREFIX.as_bytes(),
+            std::slice::from_ref(bump),
+        ]
+    }
+
+    pub fn record_buy_fee(&mut self, amount: u64) -> Result<()> {
+        self.buy_fees = Self::accrue(self.buy_fees, amount)?;
+        Ok(())
+    }
+
+    pub fn record_sell_fee(&mut self, amount: u64) -> Result<()> {
+        self.sell_fees = Self::accrue(self.sell_fees, amount)?;
+        Ok(())
+    }
+
+    pub fn record_migration_fee(&mut self, amount: u64) -> Result<()> {
+        self.migration_fees = Self::accrue(self.migration_fees, amount)?;
+        Ok(())
+    }
+
+    pub fn record_launch_fee(&mut self, amount: u64) -> Result<()> {
+        self.launch_fees = Self::accrue(self.launch_fees, amount)?;
+        Ok(())
+    }
+
+    pub fn record_finalize_sweep(&mut self, amount: u64) -> Result<()> {
+        self.finalize_sweeps = Self::accrue(self.finalize_sweeps, amount)?;
+        Ok(())
+    }
+
+    pub fn record_withdrawal(&mut self, amount: u64) -> Result<()> {
+        self.withdrawn = Self::accrue(self.withdrawn, amount)?;
+        Ok(())
+    }
+
+    pub fn record_distribution(&mut self, amount: u64) -> Result<()> {
+        self.distributed = Self::accrue(self.distributed, amount)?;
+        Ok(())
+    }
+
+    // Pay amount out of the fee vault by weight, recipients passed in the split's order.
+    // Callers record it as a withdrawal or a per trade distribution
+    pub fn distribute<'info>(
+        split: &[FeeSplitRecipient],
+        recipients: &[AccountInfo<'info>],
+        fee_vault: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        vault_signer_seeds: &[&[&[u8]]],
+        amount: u64,
+    ) -> Result<()> {
+        require!(
+            recipients.len() >= split.len(),
+            PumpFunError::InvalidFeeSplit
+        );
+
+        let amounts = FeeSplitRecipient::cuts(split, amount)?;
+        for ((share, recipient), cut) in split.iter().zip(recipients).zip(&amounts) {
+            require_keys_eq!(recipient.key(), share.wallet, PumpFunError::InvalidFeeSplit);
+
+            sol_transfer_from_vault(
+                fee_vault,
+                recipient,
+                system_program,
+                vault_signer_seeds,
+                *cut,
+            )?;
+        }
+
+        emit!(FeesDistributed {
+            recipients: split.iter().map(|share| share.wallet).collect(),
+            amounts,
+        });
+
+        Ok(())
+    }
+
+    fn accrue(total: u64, amount: u64) -> Result<u64> {
+        Ok(total
+            .checked_add(amount)
+            .ok_or(PumpFunError::MathOverflow)?)
+    }
+}
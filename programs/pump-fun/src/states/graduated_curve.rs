@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use super::{BondingCurve, MigrationTarget};
+use crate::errors::PumpFunError;
+
+// What a bonding curve shrinks to once finalized, lives at the same PDA
+#[account]
+pub struct GraduatedCurve {
+    pub token_mint: Pubkey,
+    pub creator: Pubkey,
+
+    // Where the liquidity went, kept for lookups by mint
+    pub migration_target: MigrationTarget,
+    pub pool: Pubkey,
+    pub lp_mint: Pubkey,
+}
+
+impl GraduatedCurve {
+    pub const LEN: usize = 32 + 32 + 1 + 32 + 32;
+
+    pub fn from_bonding_curve(token_mint: Pubkey, bonding_curve: &BondingCurve) -> Self {
+        Self {
+            token_mint,
+            creator: bonding_curve.creator,
+            migration_target: bonding_curve.migration_target,
+            pool: bonding_curve.pool,
+            lp_mint: bonding_curve.lp_mint,
+        }
+    }
+
+    // Read a migrated curve whether or not it has been finalized yet
+    pub fn load(token_mint: Pubkey, curve_pda: &AccountInfo) -> Result<Self> {
+        let data = curve_pda.try_borrow_data()?;
+        if let Ok(graduated) = GraduatedCurve::try_deserialize(&mut &data[..]) {
+            return Ok(graduated);
+        }
+
+        let bonding_curve = BondingCurve::try_deserialize(&mut &data[..])?;
+        require!(bonding_curve.is_migrated, PumpFunError::CurveNotMigrated);
+
+        Ok(Self::from_bonding_curve(token_mint, &bonding_curve))
+    }
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PumpFunError;
+
+#[account]
+pub struct LimitOrder {
+    // Wallet that placed the order and receives fills and refunds
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+
+    // Client chosen id so one owner can keep several orders on a curve
+    pub order_id: u64,
+
+    // 0 = buy (SOL escrowed), 1 = sell (tokens escrowed), same as swap
+    pub direction: u8,
+
+    // Escrowed SOL for buys or tokens for sells
+    pub amount: u64,
+
+    // Spot price trigger, scaled by PRICE_PRECISION
+    pub limit_price: u64,
+    pub min_amount_out: u64,
+
+    // Crank fee escrowed at placement, paid to whoever fills the order
+    pub crank_fee: u64,
+
+    // Referrer chosen at placement, credited on the fill like a referred swap
+    pub referrer: Option<Pubkey>,
+
+    // Unix timestamp after which the order can no longer be filled
+    pub expires_at: i64,
+
+    pub bump: u8,
+
+    // Reserved field for padding
+    pub reserved: [u8; 8],
+}
+
+impl LimitOrder {
+    pub const SEED_PREFIX: &'static str = "limit_order";
+    pub const LEN: usize = 32 + 32 + 8 + 1 + 8 * 4 + 8 + 33 + 1 + 8;
+
+    // get signer for limit order PDA
+    pub fn get_signer<'a>(
+        mint: &'a Pubkey,
+        owner: &'a Pubkey,
+        order_id: &'a [u8; 8],
+        bump: &'a u8,
+    ) -> [&'a [u8]; 5] {
+        [
+            Self::SEED_PREFIX.as_bytes(),
+            mint.as_ref(),
+            owner.as_ref(),
+            order_id,
+            std::slice::from_ref(bump),
+        ]
+    }
+
+    // Buys fill at or below the limit, sells at or above it
+    pub fn is_triggered(&self, spot_price: u64) -> Result<bool> {
+        match self.direction {
+            0 => Ok(spot_price <= self.limit_price),
+            1 => Ok(spot_price >= self.limit_price),
+            _ => err!(PumpFunError::IncorrectValueRange),
+        }
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now > self.expires_at
+    }
+}
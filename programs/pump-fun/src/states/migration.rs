@@ -0,0 +1,201 @@
+use anchor_lang::prelude::*;
+use raydium_amm_v3::{
+    libraries::tick_math,
+    states::{OBSERVATION_SEED, POOL_SEED, POOL_TICK_ARRAY_BITMAP_SEED, POOL_VAULT_SEED},
+};
+
+use super::{BondingCurve, Config};
+use crate::{
+    constants::PRICE_PRECISION,
+    errors::PumpFunError,
+    utils::{price_deviation_bps, sqrt_price_x64_from_amounts},
+};
+
+// Where a completed curve's reserves end up
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum MigrationTarget {
+    #[default]
+    RaydiumClmm,
+    RaydiumCpmm,
+    ConstantProduct,
+    RaydiumAmmV4,
+}
+
+// SOL and token split of a migration, shared by every target
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug)]
+pub struct MigrationAmounts {
+    pub sol_balance: u64,
+    pub token_balance: u64,
+    pub migration_fee: u64,
+    // part of the migration fee paid to the migrator, the rest goes to the fee recipient
+    pub migration_bounty: u64,
+    pub protocol_fee: u64,
+    pub remaining_sol: u64,
+}
+
+// Raydium pools need mint_0 < mint_1, so the project token can sort to either side
+pub fn sorted_mints(wsol_mint: &Pubkey, token_mint: &Pubkey) -> (Pubkey, Pubkey) {
+    if wsol_mint < token_mint {
+        (*wsol_mint, *token_mint)
+    } else {
+        (*token_mint, *wsol_mint)
+    }
+}
+
+impl MigrationAmounts {
+    pub fn compute(
+        sol_balance: u64,
+        token_balance: u64,
+        migration_fee_percentage: f64,
+        migration_bounty: u64,
+    ) -> Result<Self> {
+        // Calculate migration fee
+        let migration_fee = sol_balance
+            .checked_mul(migration_fee_percentage as u64)
+            .ok_or(PumpFunError::MathOverflow)?
+            .checked_div(100)
+            .ok_or(PumpFunError::MathOverflow)?;
+
+        // Bounty never exceeds the fee, so the pool deposit is unaffected
+        let migration_bounty = migration_bounty.min(migration_fee);
+        let protocol_fee = migration_fee - migration_bounty;
+
+        let remaining_sol = sol_balance
+            .checked_sub(migration_fee)
+            .ok_or(PumpFunError::InsufficientSolBalance)?;
+
+        Ok(Self {
+            sol_balance,
+            token_balance,
+            migration_fee,
+            migration_bounty,
+            protocol_fee,
+            remaining_sol,
+        })
+    }
+
+    // Deposits as (amount_0, amount_1) in pool mint order
+    pub fn pool_amounts(&self, token_is_mint_0: bool) -> (u64, u64) {
+        if token_is_mint_0 {
+            (self.token_balance, self.remaining_sol)
+        } else {
+            (self.remaining_sol, self.token_balance)
+        }
+    }
+}
+
+// Everything the CLMM migration derives before moving funds, shared with preview_migration
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug)]
+pub struct MigrationPlan {
+    pub amounts: MigrationAmounts,
+
+    // Deposits in pool mint order
+    pub token_is_mint_0: bool,
+    pub amount_0: u64,
+    pub amount_1: u64,
+
+    // Initial pool price, mint 1 per mint 0
+    pub sqrt_price_x64: u128,
+    pub tick: i32,
+}
+
+impl MigrationPlan {
+    pub fn compute(
+        config: &Config,
+        bonding_curve: &BondingCurve,
+        token_mint: &Pubkey,
+        wsol_mint: &Pubkey,
+        sol_balance: u64,
+        token_balance: u64,
+    ) -> Result<Self> {
+        let amounts = MigrationAmounts::compute(
+            sol_balance,
+            token_balance,
+            config.migration_fee_percentage,
+            config.migration_bounty,
+        )?;
+
+        // Deposit ratio has to agree with where the curve ended, both in lamports per token unit
+        let deposit_price = (amounts.remaining_sol as u128)
+            .checked_mul(PRICE_PRECISION)
+            .ok_or(PumpFunError::MathOverflow)?
+            .checked_div(amounts.token_balance as u128)
+            .ok_or(PumpFunError::DivisionByZero)?;
+        let deviation_bps = price_deviation_bps(
+            bonding_curve.spot_price()?,
+            u64::try_from(deposit_price).map_err(|_| error!(PumpFunError::MathOverflow))?,
+        )?;
+        require!(
+            deviation_bps <= config.migration_price_tolerance_bps as u64,
+            PumpFunError::PoolPriceMismatch
+        );
+
+        // Raydium orders the pool by mint, the price is inverted when the project token sorts first
+        let token_is_mint_0 = token_mint < wsol_mint;
+        let (amount_0, amount_1) = amounts.pool_amounts(token_is_mint_0);
+        let sqrt_price_x64 = sqrt_price_x64_from_amounts(amount_0, amount_1)?;
+
+        Ok(Self {
+            amounts,
+            token_is_mint_0,
+            amount_0,
+            amount_1,
+            sqrt_price_x64,
+            tick: tick_math::get_tick_at_sqrt_price(sqrt_price_x64)?,
+        })
+    }
+}
+
+// Returned by preview_migration, the plan plus the Raydium accounts migrate will create
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug)]
+pub struct MigrationPreview {
+    pub plan: MigrationPlan,
+
+    pub pool_state: Pubkey,
+    pub token_vault_0: Pubkey,
+    pub token_vault_1: Pubkey,
+    pub observation_state: Pubkey,
+    pub tick_array_bitmap: Pubkey,
+}
+
+impl MigrationPreview {
+    pub fn new(
+        plan: MigrationPlan,
+        amm_config: &Pubkey,
+        token_mint: &Pubkey,
+        wsol_mint: &Pubkey,
+    ) -> Self {
+        let raydium_program = raydium_amm_v3::ID;
+        let (mint_0, mint_1) = sorted_mints(wsol_mint, token_mint);
+
+        let (pool_state, _) = Pubkey::find_program_address(
+            &[POOL_SEED.as_bytes(), amm_config.as_ref(), mint_0.as_ref(), mint_1.as_ref()],
+            &raydium_program,
+        );
+        let (token_vault_0, _) = Pubkey::find_program_address(
+            &[POOL_VAULT_SEED.as_bytes(), pool_state.as_ref(), mint_0.as_ref()],
+            &raydium_program,
+        );
+        let (token_vault_1, _) = Pubkey::find_program_address(
+            &[POOL_VAULT_SEED.as_bytes(), pool_state.as_ref(), mint_1.as_ref()],
+            &raydium_program,
+        );
+        let (observation_state, _) = Pubkey::find_program_address(
+            &[OBSERVATION_SEED.as_bytes(), pool_state.as_ref()],
+            &raydium_program,
+        );
+        let (tick_array_bitmap, _) = Pubkey::find_program_address(
+            &[POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(), pool_state.as_ref()],
+            &raydium_program,
+        );
+
+        Self {
+            plan,
+            pool_state,
+            token_vault_0,
+            token_vault_1,
+            observation_state,
+            tick_array_bitmap,
+        }
+    }
+}
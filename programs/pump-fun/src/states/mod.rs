@@ -3,3 +3,33 @@ pub use config::*;
 
 pub mod bonding_curve;
 pub use bonding_curve::*;
+
+pub mod limit_order;
+pub use limit_order::*;
+
+pub mod dca_order;
+pub use dca_order::*;
+
+pub mod trigger_order;
+pub use trigger_order::*;
+
+pub mod migration;
+pub use migration::*;
+
+pub mod amm_pool;
+pub use amm_pool::*;
+
+pub mod refund_claim;
+pub use refund_claim::*;
+
+pub mod graduated_curve;
+pub use graduated_curve::*;
+
+pub mod trade_tracker;
+pub use trade_tracker::*;
+
+pub mod fee_stats;
+pub use fee_stats::*;
+
+pub mod referrer;
+pub use referrer::*;
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PumpFunError;
+
+// Frontend routing volume, holds its accrued referral fees until claimed.
+// Trades only reject a referrer owned by the trader, so a trader can still register a second
+// wallet and refer themselves. That is accepted: it only rebates the capped referral share of
+// the protocol fee, which any frontend could hand back to its users anyway.
+#[account]
+pub struct Referrer {
+    pub owner: Pubkey,
+
+    // Share of the trade fee in basis points, never above the Config cap at trade time
+    pub share_bps: u16,
+
+    // Running totals across every referred trade
+    pub referred_volume: u64,
+    pub referred_trades: u64,
+    pub fees_earned: u64,
+    pub fees_claimed: u64,
+
+    pub bump: u8,
+
+    // Reserved field for padding
+    pub reserved: [u8; 8],
+}
+
+impl Referrer {
+    pub const SEED_PREFIX: &'static str = "referrer";
+    pub const LEN: usize = 32 + 2 + 8 * 4 + 1 + 8;
+
+    pub fn record_trade(&mut self, sol_volume: u64, referral_fee: u64) -> Result<()> {
+        self.referred_volume = self
+            .referred_volume
+            .checked_add(sol_volume)
+            .ok_or(PumpFunError::MathOverflow)?;
+        self.referred_trades = self
+            .referred_trades
+            .checked_add(1)
+            .ok_or(PumpFunError::MathOverflow)?;
+        self.fees_earned = self
+            .fees_earned
+            .checked_add(referral_fee)
+            .ok_or(PumpFunError::MathOverflow)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct RefundClaim {
+    // Holder redeeming tokens on a refunding curve
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+
+    // Running totals across every redemption by this holder
+    pub tokens_burned: u64,
+    pub sol_claimed: u64,
+
+    pub bump: u8,
+
+    // Reserved field for padding
+    pub reserved: [u8; 8],
+}
+
+impl RefundClaim {
+    pub const SEED_PREFIX: &'static str = "refund_claim";
+    pub const LEN: usize = 32 + 32 + 8 * 2 + 1 + 8;
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+// Per trader and curve, its owner closes it for the rent once no sell penalty hangs on it
+#[account]
+pub struct TradeTracker {
+    // Trader and curve this record belongs to
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+
+    // Slot of the trader's latest buy on this curve, 0 before the first one
+    pub last_buy_slot: u64,
+
+    pub bump: u8,
+
+    // Reserved field for padding
+    pub reserved: [u8; 8],
+}
+
+impl TradeTracker {
+    pub const SEED_PREFIX: &'static str = "trade_tracker";
+    pub const LEN: usize = 32 + 32 + 8 + 1 + 8;
+
+    // Fill in the record, a no-op once it exists
+    pub fn track(&mut self, owner: Pubkey, token_mint: Pubkey, bump: u8) {
+        self.owner = owner;
+        self.token_mint = token_mint;
+        self.bump = bump;
+    }
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct TriggerOrder {
+    // Wallet that placed the order and receives proceeds and refunds
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+
+    // Client chosen id so one owner can keep several orders on a curve
+    pub order_id: u64,
+
+    // Escrowed tokens to sell once triggered
+    pub amount: u64,
+
+    // Spot prices scaled by PRICE_PRECISION, 0 disables that side
+    pub stop_price: u64,
+    pub take_profit_price: u64,
+
+    pub min_amount_out: u64,
+
+    // Crank fee escrowed at placement, paid to whoever executes the order
+    pub crank_fee: u64,
+
+    // Referrer chosen at placement, credited on execution like a referred swap
+    pub referrer: Option<Pubkey>,
+
+    pub bump: u8,
+
+    // Reserved field for padding
+    pub reserved: [u8; 8],
+}
+
+impl TriggerOrder {
+    pub const SEED_PREFIX: &'static str = "trigger_order";
+    pub const LEN: usize = 32 + 32 + 8 + 8 * 5 + 33 + 1 + 8;
+
+    // get signer for trigger order PDA
+    pub fn get_signer<'a>(
+        mint: &'a Pubkey,
+        owner: &'a Pubkey,
+        order_id: &'a [u8; 8],
+        bump: &'a u8,
+    ) -> [&'a [u8]; 5] {
+        [
+            Self::SEED_PREFIX.as_bytes(),
+            mint.as_ref(),
+            owner.as_ref(),
+            order_id,
+            std::slice::from_ref(bump),
+        ]
+    }
+
+    // Stop fires at or below its price, take profit at or above its price
+    pub fn is_triggered(&self, spot_price: u64) -> bool {
+        let stop_hit = self.stop_price > 0 && spot_price <= self.stop_price;
+        let take_profit_hit = self.take_profit_price > 0 && spot_price >= self.take_profit_price;
+
+        stop_hit || take_profit_hit
+    }
+}
@@ -0,0 +1,75 @@
+use anchor_lang::{prelude::*, solana_program::program::get_return_data};
+
+use crate::{errors::PumpFunError, states::SwapResult};
+
+// Typed wrappers around the generated swap CPI that hand back the trade result.
+// crate::cpi only exists with the cpi feature, so this module is gated on it too
+
+pub fn buy<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, crate::cpi::accounts::Swap<'info>>,
+    amount_in: u64,
+    min_out: u64,
+    max_price_impact_bps: Option<u16>,
+) -> Result<SwapResult> {
+    crate::cpi::swap(ctx, amount_in, 0, min_out, max_price_impact_bps)?;
+    swap_result()
+}
+
+pub fn sell<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, crate::cpi::accounts::Swap<'info>>,
+    amount_in: u64,
+    min_out: u64,
+    max_price_impact_bps: Option<u16>,
+) -> Result<SwapResult> {
+    crate::cpi::swap(ctx, amount_in, 1, min_out, max_price_impact_bps)?;
+    swap_result()
+}
+
+// Decode the result the last swap left in return data
+pub fn swap_result() -> Result<SwapResult> {
+    let (program_id, data) = get_return_data().ok_or(PumpFunError::MissingSwapResult)?;
+    require_keys_eq!(program_id, crate::ID, PumpFunError::MissingSwapResult);
+
+    SwapResult::try_from_slice(&data).map_err(|_| error!(PumpFunError::MissingSwapResult))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+
+    struct ReturnData(Pubkey, Vec<u8>);
+
+    impl SyscallStubs for ReturnData {
+        fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+            Some((self.0, self.1.clone()))
+        }
+    }
+
+    // One test, the syscall stubs are process wide
+    #[test]
+    fn swap_result_decodes_return_data() {
+        let expected = SwapResult {
+            amount_in: 1_000_000,
+            amount_out: 4_242,
+            fee_amount: 50_000,
+            virtual_sol_reserve: 1_000_950_000,
+            virtual_token_reserve: 999_995_758,
+            real_sol_reserve: 950_000,
+            real_token_reserve: 999_995_758,
+        };
+        let data = expected.try_to_vec().unwrap();
+
+        set_syscall_stubs(Box::new(ReturnData(crate::ID, data.clone())));
+        let result = swap_result().unwrap();
+        assert_eq!(result.try_to_vec().unwrap(), data);
+        assert_eq!(result.amount_out, expected.amount_out);
+
+        // Return data left by another program is not a swap result
+        set_syscall_stubs(Box::new(ReturnData(Pubkey::new_unique(), data.clone())));
+        assert!(swap_result().is_err());
+
+        set_syscall_stubs(Box::new(ReturnData(crate::ID, vec![1, 2, 3])));
+        assert!(swap_result().is_err());
+    }
+}
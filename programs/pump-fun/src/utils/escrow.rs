@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+
+use anchor_spl::token;
+
+use crate::utils::{
+    sol_transfer_from_program_account, sol_transfer_from_user, token_close_with_signer,
+    token_transfer_user, token_transfer_with_signer,
+};
+
+// Move an order's lamports (crank fee, plus SOL to spend on buys) and tokens into its escrow
+pub fn fund_order_escrow<'info>(
+    owner: &AccountInfo<'info>,
+    order: &AccountInfo<'info>,
+    owner_token_account: &AccountInfo<'info>,
+    order_token_account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    lamports: u64,
+    tokens: u64,
+) -> Result<()> {
+    sol_transfer_from_user(owner, order, system_program, lamports)?;
+
+    if tokens > 0 {
+        token_transfer_user(
+            owner_token_account,
+            order_token_account,
+            owner,
+            token_program,
+            tokens,
+        )?;
+    }
+
+    Ok(())
+}
+
+// Unwind an order's escrow once it is filled or cancelled.
+// Whatever the escrow token account holds goes back to the owner, the crank fee to whoever
+// earned it, and the escrow token account rent to the owner. The order account itself is
+// closed by its `close = owner`.
+pub fn release_order_escrow<'info>(
+    order: &AccountInfo<'info>,
+    order_token_account: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+    owner_token_account: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    crank_fee: Option<(&AccountInfo<'info>, u64)>,
+) -> Result<()> {
+    // Sweep the live balance, not the recorded amount, so tokens sent to the escrow's
+    // well-known address cannot leave it unclosable
+    let balance = token::accessor::amount(order_token_account)?;
+    if balance > 0 {
+        token_transfer_with_signer(
+            order_token_account,
+            order,
+            owner_token_account,
+            token_program,
+            signer_seeds,
+            balance,
+        )?;
+    }
+
+    if let Some((recipient, amount)) = crank_fee {
+        sol_transfer_from_program_account(order, recipient, amount)?;
+    }
+
+    token_close_with_signer(
+        order_token_account,
+        order,
+        owner,
+        token_program,
+        signer_seeds,
+    )
+}
@@ -1,12 +1,19 @@
 use anchor_lang::prelude::*;
 
+use crate::states::{MigrationTarget, TradeDirection};
+
 #[event]
 pub struct MigrationCompleted {
     pub token_mint: Pubkey,
+    pub target: MigrationTarget,
     pub sol_amount: u64,
     pub token_amount: u64,
     pub migration_fee: u64,
-    pub raydium_pool: Pubkey,
+    pub migrator: Pubkey,
+    pub migration_bounty: u64,
+    pub pool: Pubkey,
+    pub lp_mint: Pubkey,
+    pub liquidity: u128,
 }
 
 #[event]
@@ -15,6 +22,10 @@ pub struct TokenSold {
     pub sol_amount: u64,
     pub token_amount: u64,
     pub fee_amount: u64,
+    pub creator_fee: u64,
+    pub referrer: Option<Pubkey>,
+    pub referral_fee: u64,
+    pub penalty_amount: u64,
     pub price: u64,
 }
 
@@ -25,6 +36,9 @@ pub struct TokenPurchased {
     pub sol_amount: u64,
     pub token_amount: u64,
     pub fee_amount: u64, 
+    pub creator_fee: u64,
+    pub referrer: Option<Pubkey>,
+    pub referral_fee: u64,
     pub price: u64,
 }
 
@@ -34,3 +48,215 @@ pub struct CurveCompleted {
     pub final_sol_reserve: u64,
     pub final_token_reserve: u64,
 }
+
+#[event]
+pub struct PoolFeesCollected {
+    pub token_mint: Pubkey,
+    pub pool: Pubkey,
+    pub collected_by: Pubkey,
+    pub sol_fees: u64,
+    pub token_fees: u64,
+    pub creator_sol: u64,
+    pub creator_tokens: u64,
+    pub protocol_sol: u64,
+    pub protocol_tokens: u64,
+}
+
+#[event]
+pub struct CircuitBreakerTripped {
+    pub token_mint: Pubkey,
+    pub direction: TradeDirection,
+    pub reference_price: u64,
+    pub price: u64,
+    pub reference_slot: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct CurveAudited {
+    pub token_mint: Pubkey,
+    pub auditor: Pubkey,
+
+    // SOL vault balance above rent against the real SOL reserve
+    pub sol_balance: u64,
+    pub real_sol_reserve: u64,
+    pub sol_discrepancy: i64,
+
+    // Curve token account against the real token reserve it has to cover
+    pub token_balance: u64,
+    pub real_token_reserve: u64,
+    pub token_shortfall: u64,
+
+    // Mint supply plus refunded burns against the launch supply
+    pub supply_discrepancy: i64,
+
+    pub virtual_reserves_consistent: bool,
+    pub paused: bool,
+}
+
+#[event]
+pub struct CurvePauseUpdated {
+    pub token_mint: Pubkey,
+    pub paused: bool,
+}
+
+#[event]
+pub struct CurveFinalized {
+    pub token_mint: Pubkey,
+    pub pool: Pubkey,
+    pub swept_lamports: u64,
+}
+
+#[event]
+pub struct CurveRefunding {
+    pub token_mint: Pubkey,
+    pub graduation_deadline: i64,
+    pub refund_sol_reserve: u64,
+    pub refund_token_supply: u64,
+}
+
+#[event]
+pub struct RefundClaimed {
+    pub token_mint: Pubkey,
+    pub owner: Pubkey,
+    pub tokens_burned: u64,
+    pub sol_amount: u64,
+}
+
+#[event]
+pub struct OrderPlaced {
+    pub order: Pubkey,
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+    pub direction: u8,
+    pub amount: u64,
+    pub limit_price: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct OrderFilled {
+    pub order: Pubkey,
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+    pub cranker: Pubkey,
+    pub direction: u8,
+    pub amount: u64,
+    pub spot_price: u64,
+    pub crank_fee: u64,
+}
+
+#[event]
+pub struct OrderCancelled {
+    pub order: Pubkey,
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+    pub cancelled_by: Pubkey,
+    pub refunded_amount: u64,
+}
+
+#[event]
+pub struct DcaCreated {
+    pub dca_order: Pubkey,
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+    pub installment_amount: u64,
+    pub installments: u64,
+    pub interval: i64,
+}
+
+#[event]
+pub struct DcaExecuted {
+    pub dca_order: Pubkey,
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+    pub cranker: Pubkey,
+    pub sol_amount: u64,
+    pub installments_left: u64,
+    pub crank_fee: u64,
+}
+
+#[event]
+pub struct DcaCancelled {
+    pub dca_order: Pubkey,
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+    pub cancelled_by: Pubkey,
+    pub refunded_amount: u64,
+}
+
+#[event]
+pub struct TriggerOrderPlaced {
+    pub order: Pubkey,
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    pub stop_price: u64,
+    pub take_profit_price: u64,
+}
+
+#[event]
+pub struct TriggerOrderExecuted {
+    pub order: Pubkey,
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+    pub cranker: Pubkey,
+    pub amount: u64,
+    pub spot_price: u64,
+    pub crank_fee: u64,
+}
+
+#[event]
+pub struct TriggerOrderCancelled {
+    pub order: Pubkey,
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+    pub cancelled_by: Pubkey,
+    pub refunded_amount: u64,
+}
+
+#[event]
+pub struct AmmSwapped {
+    pub token_mint: Pubkey,
+    pub user: Pubkey,
+    pub direction: u8,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+    pub sol_reserve: u64,
+    pub token_reserve: u64,
+}
+
+#[event]
+pub struct FeesWithdrawn {
+    pub fee_manager: Pubkey,
+    pub amount: u64,
+    pub total_withdrawn: u64,
+}
+
+#[event]
+pub struct CreatorFeesClaimed {
+    pub token_mint: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ReferralFeesClaimed {
+    pub referrer: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub total_claimed: u64,
+}
+
+#[event]
+pub struct FeesDistributed {
+    pub recipients: Vec<Pubkey>,
+    pub amounts: Vec<u64>,
+}
+
+#[event]
+pub struct TradeTrackerClosed {
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+}
@@ -0,0 +1,170 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PumpFunError;
+use crate::states::{BondingCurve, Config, FeeStats, MigrationAmounts, MigrationTarget};
+use crate::utils::{
+    sol_transfer_from_user, sol_transfer_from_vault, sol_transfer_with_signer, sync_native,
+    token_transfer_with_signer, MigrationCompleted,
+};
+
+// Curve side of a migration, shared by every target.
+// Handlers authorize, pay the fees and release the reserves through it, run their own pool
+// CPI, then complete the curve.
+pub struct CurveMigration<'info> {
+    pub migrator: AccountInfo<'info>,
+    pub bonding_curve: AccountInfo<'info>,
+    pub curve_token_account: AccountInfo<'info>,
+    pub sol_vault: AccountInfo<'info>,
+    pub fee_vault: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub token_mint: Pubkey,
+    pub curve_bump: u8,
+    pub sol_vault_bump: u8,
+}
+
+impl<'info> CurveMigration<'info> {
+    // Anyone can migrate a completed curve unless a migrator role is configured
+    pub fn authorize(&self, config: &Config) -> Result<()> {
+        require!(
+            config.can_migrate(&self.migrator.key()),
+            PumpFunError::UnauthorizedAddress
+        );
+        Ok(())
+    }
+
+    // Accrue the migration fee into the fee vault, less the migrator's bounty
+    pub fn pay_fees(&self, amounts: &MigrationAmounts, fee_stats: &mut FeeStats) -> Result<()> {
+        let vault_seeds =
+            BondingCurve::get_sol_vault_signer(&self.token_mint, &self.sol_vault_bump);
+        let vault_signer_seeds = &[&vault_seeds[..]];
+
+        sol_transfer_from_vault(
+            &self.sol_vault,
+            &self.fee_vault,
+            &self.system_program,
+            vault_signer_seeds,
+            amounts.protocol_fee,
+        )?;
+        fee_stats.record_migration_fee(amounts.protocol_fee)?;
+
+        if amounts.migration_bounty > 0 {
+            sol_transfer_from_vault(
+                &self.sol_vault,
+                &self.migrator,
+                &self.system_program,
+                vault_signer_seeds,
+                amounts.migration_bounty,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // Move the remaining SOL and every curve token to the pool side, syncing the SOL
+    // destination when it is a WSOL account
+    pub fn release_reserves(
+        &self,
+        amounts: &MigrationAmounts,
+        sol_destination: &AccountInfo<'info>,
+        token_destination: &AccountInfo<'info>,
+        wrap_sol: bool,
+    ) -> Result<()> {
+        let vault_seeds =
+            BondingCurve::get_sol_vault_signer(&self.token_mint, &self.sol_vault_bump);
+        sol_transfer_from_vault(
+            &self.sol_vault,
+            sol_destination,
+            &self.system_program,
+            &[&vault_seeds[..]],
+            amounts.remaining_sol,
+        )?;
+        if wrap_sol {
+            sync_native(sol_destination, &self.token_program)?;
+        }
+
+        let seeds = BondingCurve::get_signer(&self.token_mint, &self.curve_bump);
+        token_transfer_with_signer(
+            &self.curve_token_account,
+            &self.bonding_curve,
+            token_destination,
+            &self.token_program,
+            &[&seeds[..]],
+            amounts.token_balance,
+        )
+    }
+
+    // The migrator fronts what the position authority pays Raydium, returns the balance to refund against
+    pub fn front_position_budget(
+        &self,
+        position_authority: &AccountInfo<'info>,
+        lamports: u64,
+    ) -> Result<u64> {
+        let lamports_before = position_authority.lamports();
+        sol_transfer_from_user(
+            &self.migrator,
+            position_authority,
+            &self.system_program,
+            lamports,
+        )?;
+        Ok(lamports_before)
+    }
+
+    // Hand whatever rent and creation fee went unused back to the migrator
+    pub fn refund_position_budget(
+        &self,
+        position_authority: &AccountInfo<'info>,
+        lamports_before: u64,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let unused_lamports = position_authority
+            .lamports()
+            .saturating_sub(lamports_before);
+        if unused_lamports > 0 {
+            sol_transfer_with_signer(
+                position_authority,
+                &self.migrator,
+                &self.system_program,
+                signer_seeds,
+                unused_lamports,
+            )?;
+        }
+        Ok(())
+    }
+
+    // Record where the liquidity went and mark the curve migrated
+    pub fn complete(
+        &self,
+        bonding_curve: &mut BondingCurve,
+        target: MigrationTarget,
+        amounts: &MigrationAmounts,
+        pool: Pubkey,
+        lp_mint: Pubkey,
+        liquidity: u128,
+    ) -> Result<()> {
+        bonding_curve.pool = pool;
+        bonding_curve.lp_mint = lp_mint;
+
+        // Reserves left the curve, the vault keeps its rent reserve and any donation until finalize reclaims them
+        bonding_curve.real_sol_reserve = 0;
+        bonding_curve.real_token_reserve = 0;
+        bonding_curve.check_sol_vault(&self.sol_vault)?;
+
+        bonding_curve.is_migrated = true;
+
+        emit!(MigrationCompleted {
+            token_mint: self.token_mint,
+            target,
+            sol_amount: amounts.remaining_sol,
+            token_amount: amounts.token_balance,
+            migration_fee: amounts.migration_fee,
+            migrator: self.migrator.key(),
+            migration_bounty: amounts.migration_bounty,
+            pool,
+            lp_mint,
+            liquidity,
+        });
+
+        Ok(())
+    }
+}
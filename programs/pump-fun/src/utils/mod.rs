@@ -1,5 +1,19 @@
 pub mod transfer;
 pub use transfer::*;
 
+pub mod escrow;
+pub use escrow::*;
+
+pub mod migration;
+pub use migration::*;
+
 pub mod events;
 pub use events::*;
+
+#[cfg(feature = "cpi")]
+pub mod cpi;
+
+pub mod price;
+pub use price::*;
+
+pub mod raydium_amm_v4;
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use raydium_amm_v3::libraries::{big_num::U256, tick_math};
+
+use crate::{constants::BPS_DENOMINATOR, errors::PumpFunError};
+
+// Initial CLMM price for a deposit of amount_0 of token 0 against amount_1 of token 1,
+// as sqrt(amount_1 / amount_0) in Q64.64.
+// Raydium prices are ratios of raw base units, so the 10^(TOKEN_DECIMAL - 9) shift between
+// a UI price in SOL per token and this ratio is carried by the raw amounts themselves.
+pub fn sqrt_price_x64_from_amounts(amount_0: u64, amount_1: u64) -> Result<u128> {
+    require!(amount_0 > 0 && amount_1 > 0, PumpFunError::DivisionByZero);
+
+    let ratio_x128 = (U256::from(amount_1) << 128) / U256::from(amount_0);
+    let sqrt_price_x64 = ratio_x128.integer_sqrt().as_u128();
+
+    require!(
+        sqrt_price_x64 >= tick_math::MIN_SQRT_PRICE_X64
+            && sqrt_price_x64 < tick_math::MAX_SQRT_PRICE_X64,
+        PumpFunError::InvalidPoolPrice
+    );
+
+    Ok(sqrt_price_x64)
+}
+
+// Liquidity of a constant product deposit, sqrt(amount_a * amount_b) rounded down
+pub fn constant_product_liquidity(amount_a: u64, amount_b: u64) -> u128 {
+    (U256::from(amount_a) * U256::from(amount_b))
+        .integer_sqrt()
+        .as_u128()
+}
+
+// Widest tick range usable with the pool's tick spacing
+pub fn full_range_ticks(tick_spacing: u16) -> (i32, i32) {
+    let tick_spacing = tick_spacing as i32;
+    (
+        tick_math::MIN_TICK / tick_spacing * tick_spacing,
+        tick_math::MAX_TICK / tick_spacing * tick_spacing,
+    )
+}
+
+// Distance between two prices in basis points of the reference price
+pub fn price_deviation_bps(reference_price: u64, price: u64) -> Result<u64> {
+    require!(reference_price > 0, PumpFunError::DivisionByZero);
+
+    let deviation = (price.abs_diff(reference_price) as u128)
+        .checked_mul(BPS_DENOMINATOR as u128)
+        .ok_or(PumpFunError::MathOverflow)?
+        / reference_price as u128;
+
+    u64::try_from(deviation).map_err(|_| error!(PumpFunError::MathOverflow))
+}
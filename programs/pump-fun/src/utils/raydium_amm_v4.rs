@@ -0,0 +1,143 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+    },
+};
+
+use crate::errors::PumpFunError;
+
+// Raydium AMM v4 is a native program without a CPI crate, so its ids, seeds and
+// the initialize2 layout live here
+
+pub mod program {
+    anchor_lang::declare_id!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+}
+
+// OpenBook market program the pool is bound to
+pub mod market_program {
+    anchor_lang::declare_id!("srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX");
+}
+
+pub const AMM_AUTHORITY_SEED: &str = "amm authority";
+pub const AMM_CONFIG_SEED: &str = "amm_config_account_seed";
+pub const AMM_ASSOCIATED_SEED: &str = "amm_associated_seed";
+pub const OPEN_ORDER_ASSOCIATED_SEED: &str = "open_order_associated_seed";
+pub const LP_MINT_ASSOCIATED_SEED: &str = "lp_mint_associated_seed";
+pub const COIN_VAULT_ASSOCIATED_SEED: &str = "coin_vault_associated_seed";
+pub const PC_VAULT_ASSOCIATED_SEED: &str = "pc_vault_associated_seed";
+pub const TARGET_ASSOCIATED_SEED: &str = "target_associated_seed";
+
+const INITIALIZE2_TAG: u8 = 1;
+
+// AmmConfig is pnl_owner, cancel_owner, 28 u64 of padding, then 31 u64 of padding before create_pool_fee
+const CONFIG_CREATE_POOL_FEE_OFFSET: usize = 32 + 32 + 8 * 28 + 8 * 31;
+
+// Raydium's pool creation fee, charged to the pool creator on initialize2
+pub fn create_pool_fee(amm_config: &AccountInfo) -> Result<u64> {
+    require_keys_eq!(*amm_config.owner, program::ID, PumpFunError::InvalidAmmConfig);
+
+    let data = amm_config.try_borrow_data()?;
+    let fee = data
+        .get(CONFIG_CREATE_POOL_FEE_OFFSET..CONFIG_CREATE_POOL_FEE_OFFSET + 8)
+        .ok_or(PumpFunError::InvalidAmmConfig)?;
+
+    Ok(u64::from_le_bytes(fee.try_into().unwrap()))
+}
+
+pub struct Initialize2<'a, 'info> {
+    pub token_program: &'a AccountInfo<'info>,
+    pub associated_token_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub rent: &'a AccountInfo<'info>,
+    pub amm: &'a AccountInfo<'info>,
+    pub amm_authority: &'a AccountInfo<'info>,
+    pub amm_open_orders: &'a AccountInfo<'info>,
+    pub lp_mint: &'a AccountInfo<'info>,
+    pub coin_mint: &'a AccountInfo<'info>,
+    pub pc_mint: &'a AccountInfo<'info>,
+    pub coin_vault: &'a AccountInfo<'info>,
+    pub pc_vault: &'a AccountInfo<'info>,
+    pub target_orders: &'a AccountInfo<'info>,
+    pub amm_config: &'a AccountInfo<'info>,
+    pub create_fee_destination: &'a AccountInfo<'info>,
+    pub market_program: &'a AccountInfo<'info>,
+    pub market: &'a AccountInfo<'info>,
+    pub user_wallet: &'a AccountInfo<'info>,
+    pub user_token_coin: &'a AccountInfo<'info>,
+    pub user_token_pc: &'a AccountInfo<'info>,
+    pub user_token_lp: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> Initialize2<'a, 'info> {
+    // Accounts in the order Raydium's initialize2 expects them
+    fn account_infos(&self) -> [&'a AccountInfo<'info>; 21] {
+        [
+            self.token_program,
+            self.associated_token_program,
+            self.system_program,
+            self.rent,
+            self.amm,
+            self.amm_authority,
+            self.amm_open_orders,
+            self.lp_mint,
+            self.coin_mint,
+            self.pc_mint,
+            self.coin_vault,
+            self.pc_vault,
+            self.target_orders,
+            self.amm_config,
+            self.create_fee_destination,
+            self.market_program,
+            self.market,
+            self.user_wallet,
+            self.user_token_coin,
+            self.user_token_pc,
+            self.user_token_lp,
+        ]
+    }
+
+    pub fn invoke_signed(
+        &self,
+        raydium_program: &AccountInfo<'info>,
+        nonce: u8,
+        open_time: u64,
+        init_pc_amount: u64,
+        init_coin_amount: u64,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let infos = self.account_infos();
+
+        // The wallet signs, Raydium writes to the pool accounts, fee receiver and user accounts
+        let accounts = infos
+            .iter()
+            .enumerate()
+            .map(|(index, info)| match index {
+                0..=3 | 5 | 8 | 9 | 13 | 15 | 16 => AccountMeta::new_readonly(*info.key, false),
+                17 => AccountMeta::new(*info.key, true),
+                _ => AccountMeta::new(*info.key, false),
+            })
+            .collect();
+
+        let mut data = Vec::with_capacity(26);
+        data.push(INITIALIZE2_TAG);
+        data.push(nonce);
+        data.extend_from_slice(&open_time.to_le_bytes());
+        data.extend_from_slice(&init_pc_amount.to_le_bytes());
+        data.extend_from_slice(&init_coin_amount.to_le_bytes());
+
+        let instruction = Instruction {
+            program_id: *raydium_program.key,
+            accounts,
+            data,
+        };
+
+        let mut account_infos: Vec<AccountInfo<'info>> =
+            infos.iter().map(|info| (*info).clone()).collect();
+        account_infos.push(raydium_program.clone());
+
+        invoke_signed(&instruction, &account_infos, signer_seeds)?;
+        Ok(())
+    }
+}
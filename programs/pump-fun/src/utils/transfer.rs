@@ -8,8 +8,10 @@ use anchor_lang::{
 
 use anchor_spl::token;
 
+use crate::errors::PumpFunError;
+
 pub fn sol_transfer_from_user<'info>(
-    signer: &Signer<'info>,
+    signer: &AccountInfo<'info>,
     destination: &AccountInfo<'info>,
     system_program: &AccountInfo<'info>,
     amount: u64,
@@ -50,6 +52,37 @@ pub fn sol_transfer_with_signer<'info>(
     Ok(())
 }
 
+// pay out of a data-less vault PDA, never dipping below its rent-exempt minimum
+pub fn sol_transfer_from_vault<'info>(
+    vault: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    let remaining = vault
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(PumpFunError::InsufficientSolBalance)?;
+    require!(
+        remaining >= Rent::get()?.minimum_balance(vault.data_len()),
+        PumpFunError::VaultBelowRentExempt
+    );
+
+    sol_transfer_with_signer(vault, destination, system_program, signer_seeds, amount)
+}
+
+// move lamports out of an account owned by this program, system transfer rejects those
+pub fn sol_transfer_from_program_account<'info>(
+    source: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    source.sub_lamports(amount)?;
+    destination.add_lamports(amount)?;
+    Ok(())
+}
+
 pub fn token_transfer_user<'info>(
     from: &AccountInfo<'info>,
     to: &AccountInfo<'info>,
@@ -70,6 +103,26 @@ pub fn token_transfer_user<'info>(
     Ok(())
 }
 
+pub fn token_burn_user<'info>(
+    mint: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_ctx = CpiContext::new(
+        token_program.to_account_info(),
+        token::Burn {
+            mint: mint.to_account_info(),
+            from: from.to_account_info(),
+            authority: authority.to_account_info(),
+        },
+    );
+    token::burn(cpi_ctx, amount)?;
+
+    Ok(())
+}
+
 pub fn token_transfer_with_signer<'info>(
     from: &AccountInfo<'info>,
     authority: &AccountInfo<'info>,
@@ -92,3 +145,64 @@ pub fn token_transfer_with_signer<'info>(
 
     Ok(())
 }
+
+pub fn token_burn_with_signer<'info>(
+    mint: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    let cpi_ctx: CpiContext<_> = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        token::Burn {
+            mint: mint.to_account_info(),
+            from: from.to_account_info(),
+            authority: authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    token::burn(cpi_ctx, amount)?;
+
+    Ok(())
+}
+
+pub fn token_close_with_signer<'info>(
+    account: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let cpi_ctx: CpiContext<_> = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        token::CloseAccount {
+            account: account.to_account_info(),
+            destination: destination.to_account_info(),
+            authority: authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    token::close_account(cpi_ctx)?;
+
+    Ok(())
+}
+
+pub fn sync_native<'info>(
+    account: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let cpi_ctx = CpiContext::new(
+        token_program.to_account_info(),
+        token::SyncNative {
+            account: account.to_account_info(),
+        },
+    );
+
+    token::sync_native(cpi_ctx)?;
+
+    Ok(())
+}
@@ -0,0 +1,261 @@
+// Local stand-in for Raydium AMM v4, loaded at the Raydium address by the test validator.
+// Raydium's v4 is a native program, so initialize2 arrives as raw instruction data and is
+// handled by the fallback; its account order and data layout match pump-fun's raydium_amm_v4
+// helper. Only pool creation is modelled and the OpenBook market is not inspected.
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, CreateAccount},
+};
+use anchor_spl::{
+    associated_token::{self, Create},
+    token::{self, InitializeAccount3, InitializeMint2, Mint, MintTo, Transfer},
+};
+
+declare_id!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+
+pub const AMM_AUTHORITY_SEED: &str = "amm authority";
+pub const AMM_CONFIG_SEED: &str = "amm_config_account_seed";
+pub const AMM_ASSOCIATED_SEED: &str = "amm_associated_seed";
+pub const LP_MINT_ASSOCIATED_SEED: &str = "lp_mint_associated_seed";
+pub const COIN_VAULT_ASSOCIATED_SEED: &str = "coin_vault_associated_seed";
+pub const PC_VAULT_ASSOCIATED_SEED: &str = "pc_vault_associated_seed";
+
+const INITIALIZE2_TAG: u8 = 1;
+
+// Raydium's AmmConfig: pnl_owner, cancel_owner, two padding blocks, then create_pool_fee
+pub const AMM_CONFIG_LEN: usize = 32 + 32 + 8 * 28 + 8 * 31 + 8;
+const CREATE_POOL_FEE_OFFSET: usize = AMM_CONFIG_LEN - 8;
+
+// Stub pool record: nonce, open_time, coin/pc/lp mints and the two vaults
+const AMM_LEN: usize = 1 + 8 + 32 * 5;
+
+#[program]
+pub mod raydium_amm_v4_stub {
+    use super::*;
+
+    // Writes a raw config account the way Raydium lays it out, without an Anchor discriminator
+    pub fn create_config(ctx: Context<CreateConfig>, create_pool_fee: u64) -> Result<()> {
+        let space = AMM_CONFIG_LEN as u64;
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                CreateAccount {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: ctx.accounts.amm_config.to_account_info(),
+                },
+                &[&[AMM_CONFIG_SEED.as_bytes(), &[ctx.bumps.amm_config]]],
+            ),
+            Rent::get()?.minimum_balance(AMM_CONFIG_LEN),
+            space,
+            &crate::ID,
+        )?;
+
+        let mut data = ctx.accounts.amm_config.try_borrow_mut_data()?;
+        data[..32].copy_from_slice(ctx.accounts.admin.key.as_ref());
+        data[32..64].copy_from_slice(ctx.accounts.admin.key.as_ref());
+        data[CREATE_POOL_FEE_OFFSET..].copy_from_slice(&create_pool_fee.to_le_bytes());
+        Ok(())
+    }
+
+    pub fn initialize2<'info>(
+        program_id: &Pubkey,
+        accounts: &'info [AccountInfo<'info>],
+        data: &[u8],
+    ) -> Result<()> {
+        require!(
+            data.len() == 26 && data[0] == INITIALIZE2_TAG,
+            StubError::InvalidInstruction
+        );
+        let nonce = data[1];
+        let open_time = u64::from_le_bytes(data[2..10].try_into().unwrap());
+        let init_pc_amount = u64::from_le_bytes(data[10..18].try_into().unwrap());
+        let init_coin_amount = u64::from_le_bytes(data[18..26].try_into().unwrap());
+
+        let [token_program, associated_token_program, system_program, _rent, amm, amm_authority, _amm_open_orders, lp_mint, coin_mint, pc_mint, coin_vault, pc_vault, _target_orders, amm_config, create_fee_destination, _market_program, market, user_wallet, user_token_coin, user_token_pc, user_token_lp] =
+            accounts
+        else {
+            return err!(StubError::InvalidInstruction);
+        };
+        require!(user_wallet.is_signer, ErrorCode::AccountNotSigner);
+        require_keys_eq!(*token_program.key, token::ID);
+
+        let authority_seeds: &[&[u8]] = &[AMM_AUTHORITY_SEED.as_bytes(), &[nonce]];
+        let authority = Pubkey::create_program_address(authority_seeds, program_id)
+            .map_err(|_| StubError::InvalidNonce)?;
+        require_keys_eq!(*amm_authority.key, authority, StubError::InvalidNonce);
+
+        let associated = |account: &AccountInfo, seed: &str| -> Result<u8> {
+            let (address, bump) = Pubkey::find_program_address(
+                &[program_id.as_ref(), market.key.as_ref(), seed.as_bytes()],
+                program_id,
+            );
+            require_keys_eq!(*account.key, address, StubError::InvalidProgramAddress);
+            Ok(bump)
+        };
+        let amm_bump = associated(amm, AMM_ASSOCIATED_SEED)?;
+        let lp_mint_bump = associated(lp_mint, LP_MINT_ASSOCIATED_SEED)?;
+        let coin_vault_bump = associated(coin_vault, COIN_VAULT_ASSOCIATED_SEED)?;
+        let pc_vault_bump = associated(pc_vault, PC_VAULT_ASSOCIATED_SEED)?;
+
+        // Creation fee comes from the config
+        let (config_address, _) =
+            Pubkey::find_program_address(&[AMM_CONFIG_SEED.as_bytes()], program_id);
+        require_keys_eq!(*amm_config.key, config_address, StubError::InvalidProgramAddress);
+        let create_pool_fee = {
+            let config = amm_config.try_borrow_data()?;
+            u64::from_le_bytes(config[CREATE_POOL_FEE_OFFSET..].try_into().unwrap())
+        };
+        if create_pool_fee > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    system_program::Transfer {
+                        from: user_wallet.clone(),
+                        to: create_fee_destination.clone(),
+                    },
+                ),
+                create_pool_fee,
+            )?;
+        }
+
+        let rent = Rent::get()?;
+        let create = |account: &AccountInfo<'info>, seed: &str, bump: u8, space: usize, owner: &Pubkey| {
+            system_program::create_account(
+                CpiContext::new_with_signer(
+                    system_program.clone(),
+                    CreateAccount {
+                        from: user_wallet.clone(),
+                        to: account.clone(),
+                    },
+                    &[&[program_id.as_ref(), market.key.as_ref(), seed.as_bytes(), &[bump]]],
+                ),
+                rent.minimum_balance(space),
+                space as u64,
+                owner,
+            )
+        };
+
+        // LP mint shares the coin's decimals, as on Raydium
+        let coin_decimals = Mint::try_deserialize(&mut &coin_mint.try_borrow_data()?[..])?.decimals;
+        create(lp_mint, LP_MINT_ASSOCIATED_SEED, lp_mint_bump, Mint::LEN, &token::ID)?;
+        token::initialize_mint2(
+            CpiContext::new(
+                token_program.clone(),
+                InitializeMint2 {
+                    mint: lp_mint.clone(),
+                },
+            ),
+            coin_decimals,
+            &authority,
+            None,
+        )?;
+
+        for (vault, mint, seed, bump) in [
+            (coin_vault, coin_mint, COIN_VAULT_ASSOCIATED_SEED, coin_vault_bump),
+            (pc_vault, pc_mint, PC_VAULT_ASSOCIATED_SEED, pc_vault_bump),
+        ] {
+            create(vault, seed, bump, token::TokenAccount::LEN, &token::ID)?;
+            token::initialize_account3(CpiContext::new(
+                token_program.clone(),
+                InitializeAccount3 {
+                    account: vault.clone(),
+                    mint: mint.clone(),
+                    authority: amm_authority.clone(),
+                },
+            ))?;
+        }
+
+        for (from, to, amount) in [
+            (user_token_coin, coin_vault, init_coin_amount),
+            (user_token_pc, pc_vault, init_pc_amount),
+        ] {
+            token::transfer(
+                CpiContext::new(
+                    token_program.clone(),
+                    Transfer {
+                        from: from.clone(),
+                        to: to.clone(),
+                        authority: user_wallet.clone(),
+                    },
+                ),
+                amount,
+            )?;
+        }
+
+        create(amm, AMM_ASSOCIATED_SEED, amm_bump, AMM_LEN, program_id)?;
+        {
+            let mut state = amm.try_borrow_mut_data()?;
+            state[0] = nonce;
+            state[1..9].copy_from_slice(&open_time.to_le_bytes());
+            for (index, key) in [coin_mint, pc_mint, lp_mint, coin_vault, pc_vault]
+                .iter()
+                .enumerate()
+            {
+                let start = 9 + 32 * index;
+                state[start..start + 32].copy_from_slice(key.key.as_ref());
+            }
+        }
+
+        // All initial liquidity goes to the creator
+        associated_token::create(CpiContext::new(
+            associated_token_program.clone(),
+            Create {
+                payer: user_wallet.clone(),
+                associated_token: user_token_lp.clone(),
+                authority: user_wallet.clone(),
+                mint: lp_mint.clone(),
+                system_program: system_program.clone(),
+                token_program: token_program.clone(),
+            },
+        ))?;
+        let liquidity = isqrt(init_coin_amount as u128 * init_pc_amount as u128) as u64;
+        token::mint_to(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                MintTo {
+                    mint: lp_mint.clone(),
+                    to: user_token_lp.clone(),
+                    authority: amm_authority.clone(),
+                },
+                &[authority_seeds],
+            ),
+            liquidity,
+        )?;
+        Ok(())
+    }
+}
+
+fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+#[derive(Accounts)]
+pub struct CreateConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: Raw Raydium config, created here
+    #[account(mut, seeds = [AMM_CONFIG_SEED.as_bytes()], bump)]
+    pub amm_config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[error_code]
+pub enum StubError {
+    #[msg("Unsupported instruction")]
+    InvalidInstruction,
+    #[msg("Nonce does not derive the AMM authority")]
+    InvalidNonce,
+    #[msg("Account is not the expected program address")]
+    InvalidProgramAddress,
+}
@@ -0,0 +1,515 @@
+// Local stand-in for Raydium CLMM, loaded at the Raydium address by the test validator.
+// Instruction names, arguments and account order match what pump-fun calls through the
+// raydium-amm-v3 CPI crate; the pool math is replaced with plain transfers.
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, MintTo, Token, TokenAccount, Transfer},
+};
+
+declare_id!("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");
+
+pub const AMM_CONFIG_SEED: &str = "amm_config";
+pub const POOL_SEED: &str = "pool";
+pub const POOL_VAULT_SEED: &str = "pool_vault";
+pub const OBSERVATION_SEED: &str = "observation";
+pub const POOL_TICK_ARRAY_BITMAP_SEED: &str = "pool_tick_array_bitmap_extension";
+pub const POSITION_SEED: &str = "position";
+
+#[program]
+pub mod raydium_clmm_stub {
+    use super::*;
+
+    pub fn create_amm_config(
+        ctx: Context<CreateAmmConfig>,
+        index: u16,
+        tick_spacing: u16,
+        trade_fee_rate: u32,
+        protocol_fee_rate: u32,
+        fund_fee_rate: u32,
+    ) -> Result<()> {
+        let amm_config = &mut ctx.accounts.amm_config;
+        amm_config.bump = ctx.bumps.amm_config;
+        amm_config.index = index;
+        amm_config.owner = ctx.accounts.owner.key();
+        amm_config.protocol_fee_rate = protocol_fee_rate;
+        amm_config.trade_fee_rate = trade_fee_rate;
+        amm_config.tick_spacing = tick_spacing;
+        amm_config.fund_fee_rate = fund_fee_rate;
+        amm_config.fund_owner = ctx.accounts.owner.key();
+        Ok(())
+    }
+
+    pub fn create_pool(ctx: Context<CreatePool>, sqrt_price_x64: u128, open_time: u64) -> Result<()> {
+        require_keys_neq!(
+            ctx.accounts.token_mint_0.key(),
+            ctx.accounts.token_mint_1.key()
+        );
+        require!(
+            ctx.accounts.token_mint_0.key() < ctx.accounts.token_mint_1.key(),
+            StubError::UnsortedMints
+        );
+
+        let pool_state = &mut ctx.accounts.pool_state;
+        pool_state.bump = ctx.bumps.pool_state;
+        pool_state.amm_config = ctx.accounts.amm_config.key();
+        pool_state.token_mint_0 = ctx.accounts.token_mint_0.key();
+        pool_state.token_mint_1 = ctx.accounts.token_mint_1.key();
+        pool_state.token_vault_0 = ctx.accounts.token_vault_0.key();
+        pool_state.token_vault_1 = ctx.accounts.token_vault_1.key();
+        pool_state.sqrt_price_x64 = sqrt_price_x64;
+        pool_state.open_time = open_time;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn open_position<'info>(
+        ctx: Context<'_, '_, '_, 'info, OpenPosition<'info>>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        _tick_array_lower_start_index: i32,
+        _tick_array_upper_start_index: i32,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+    ) -> Result<()> {
+        require!(tick_lower_index < tick_upper_index, StubError::InvalidTickRange);
+        require!(liquidity > 0, StubError::ZeroLiquidity);
+
+        let pool_state = &mut ctx.accounts.pool_state;
+        require_keys_eq!(ctx.accounts.token_vault_0.key(), pool_state.token_vault_0);
+        require_keys_eq!(ctx.accounts.token_vault_1.key(), pool_state.token_vault_1);
+        pool_state.liquidity = pool_state
+            .liquidity
+            .checked_add(liquidity)
+            .ok_or(StubError::MathOverflow)?;
+
+        // Deposit both sides in full, Raydium would take at most these
+        for (from, to, amount) in [
+            (&ctx.accounts.token_account_0, &ctx.accounts.token_vault_0, amount_0_max),
+            (&ctx.accounts.token_account_1, &ctx.accounts.token_vault_1, amount_1_max),
+        ] {
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: from.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: ctx.accounts.payer.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+        }
+
+        // The position NFT is the only proof of ownership
+        let (amm_config, mint_0, mint_1) =
+            (pool_state.amm_config, pool_state.token_mint_0, pool_state.token_mint_1);
+        let pool_seeds: &[&[u8]] = &[
+            POOL_SEED.as_bytes(),
+            amm_config.as_ref(),
+            mint_0.as_ref(),
+            mint_1.as_ref(),
+            &[pool_state.bump],
+        ];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.position_nft_mint.to_account_info(),
+                    to: ctx.accounts.position_nft_account.to_account_info(),
+                    authority: pool_state.to_account_info(),
+                },
+                &[pool_seeds],
+            ),
+            1,
+        )?;
+
+        let personal_position = &mut ctx.accounts.personal_position;
+        personal_position.bump = ctx.bumps.personal_position;
+        personal_position.nft_mint = ctx.accounts.position_nft_mint.key();
+        personal_position.pool_id = pool_state.key();
+        personal_position.tick_lower_index = tick_lower_index;
+        personal_position.tick_upper_index = tick_upper_index;
+        personal_position.liquidity = liquidity;
+        Ok(())
+    }
+
+    pub fn decrease_liquidity_v2<'info>(
+        ctx: Context<'_, '_, '_, 'info, DecreaseLiquidityV2<'info>>,
+        liquidity: u128,
+        amount_0_min: u64,
+        amount_1_min: u64,
+    ) -> Result<()> {
+        // Only fee collection is modelled, the principal stays in the pool
+        require!(liquidity == 0, StubError::LiquidityRemovalUnsupported);
+
+        let personal_position = &mut ctx.accounts.personal_position;
+        let fees_0 = std::mem::take(&mut personal_position.token_fees_owed_0);
+        let fees_1 = std::mem::take(&mut personal_position.token_fees_owed_1);
+        require!(
+            fees_0 >= amount_0_min && fees_1 >= amount_1_min,
+            StubError::PriceSlippageCheck
+        );
+
+        let pool_state = &ctx.accounts.pool_state;
+        let pool_seeds: &[&[u8]] = &[
+            POOL_SEED.as_bytes(),
+            pool_state.amm_config.as_ref(),
+            pool_state.token_mint_0.as_ref(),
+            pool_state.token_mint_1.as_ref(),
+            &[pool_state.bump],
+        ];
+        for (from, to, amount) in [
+            (&ctx.accounts.token_vault_0, &ctx.accounts.recipient_token_account_0, fees_0),
+            (&ctx.accounts.token_vault_1, &ctx.accounts.recipient_token_account_1, fees_1),
+        ] {
+            if amount > 0 {
+                token::transfer(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        Transfer {
+                            from: from.to_account_info(),
+                            to: to.to_account_info(),
+                            authority: pool_state.to_account_info(),
+                        },
+                        &[pool_seeds],
+                    ),
+                    amount,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    // Test hook standing in for swaps: pays fees into the vaults and owes them to a position
+    pub fn accrue_fees(ctx: Context<AccrueFees>, amount_0: u64, amount_1: u64) -> Result<()> {
+        for (from, to, amount) in [
+            (&ctx.accounts.payer_token_0, &ctx.accounts.token_vault_0, amount_0),
+            (&ctx.accounts.payer_token_1, &ctx.accounts.token_vault_1, amount_1),
+        ] {
+            if amount > 0 {
+                token::transfer(
+                    CpiContext::new(
+                        ctx.accounts.token_program.to_account_info(),
+                        Transfer {
+                            from: from.to_account_info(),
+                            to: to.to_account_info(),
+                            authority: ctx.accounts.payer.to_account_info(),
+                        },
+                    ),
+                    amount,
+                )?;
+            }
+        }
+
+        let personal_position = &mut ctx.accounts.personal_position;
+        personal_position.token_fees_owed_0 = personal_position
+            .token_fees_owed_0
+            .checked_add(amount_0)
+            .ok_or(StubError::MathOverflow)?;
+        personal_position.token_fees_owed_1 = personal_position
+            .token_fees_owed_1
+            .checked_add(amount_1)
+            .ok_or(StubError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(index: u16)]
+pub struct CreateAmmConfig<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        seeds = [AMM_CONFIG_SEED.as_bytes(), &index.to_be_bytes()],
+        bump,
+        payer = owner,
+        space = AmmConfig::LEN,
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreatePool<'info> {
+    #[account(mut)]
+    pub pool_creator: Signer<'info>,
+
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    #[account(
+        init,
+        seeds = [
+            POOL_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+            token_mint_0.key().as_ref(),
+            token_mint_1.key().as_ref(),
+        ],
+        bump,
+        payer = pool_creator,
+        space = PoolState::LEN,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    pub token_mint_0: Box<Account<'info, Mint>>,
+
+    pub token_mint_1: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        seeds = [POOL_VAULT_SEED.as_bytes(), pool_state.key().as_ref(), token_mint_0.key().as_ref()],
+        bump,
+        payer = pool_creator,
+        token::mint = token_mint_0,
+        token::authority = pool_state,
+        token::token_program = token_program_0,
+    )]
+    pub token_vault_0: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        seeds = [POOL_VAULT_SEED.as_bytes(), pool_state.key().as_ref(), token_mint_1.key().as_ref()],
+        bump,
+        payer = pool_creator,
+        token::mint = token_mint_1,
+        token::authority = pool_state,
+        token::token_program = token_program_1,
+    )]
+    pub token_vault_1: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Only its address is checked, the stub keeps no observations
+    #[account(seeds = [OBSERVATION_SEED.as_bytes(), pool_state.key().as_ref()], bump)]
+    pub observation_state: UncheckedAccount<'info>,
+
+    /// CHECK: Only its address is checked, the stub keeps no tick arrays
+    #[account(seeds = [POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(), pool_state.key().as_ref()], bump)]
+    pub tick_array_bitmap: UncheckedAccount<'info>,
+
+    pub token_program_0: Program<'info, Token>,
+    pub token_program_1: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Receives the position NFT
+    pub position_nft_owner: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        mint::decimals = 0,
+        mint::authority = pool_state,
+        payer = payer,
+    )]
+    pub position_nft_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        associated_token::mint = position_nft_mint,
+        associated_token::authority = position_nft_owner,
+        payer = payer,
+    )]
+    pub position_nft_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: The stub writes no metadata
+    #[account(mut)]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    /// CHECK: The stub keeps no per-range state
+    #[account(mut)]
+    pub protocol_position: UncheckedAccount<'info>,
+
+    /// CHECK: The stub keeps no tick arrays
+    #[account(mut)]
+    pub tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: The stub keeps no tick arrays
+    #[account(mut)]
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        seeds = [POSITION_SEED.as_bytes(), position_nft_mint.key().as_ref()],
+        bump,
+        payer = payer,
+        space = PersonalPositionState::LEN,
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut, token::mint = pool_state.token_mint_0)]
+    pub token_account_0: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = pool_state.token_mint_1)]
+    pub token_account_1: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub token_vault_0: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub token_vault_1: Box<Account<'info, TokenAccount>>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// CHECK: The stub writes no metadata
+    pub metadata_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DecreaseLiquidityV2<'info> {
+    pub nft_owner: Signer<'info>,
+
+    #[account(
+        token::authority = nft_owner,
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+    )]
+    pub nft_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    /// CHECK: The stub keeps no per-range state
+    #[account(mut)]
+    pub protocol_position: UncheckedAccount<'info>,
+
+    #[account(mut, address = pool_state.token_vault_0)]
+    pub token_vault_0: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, address = pool_state.token_vault_1)]
+    pub token_vault_1: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: The stub keeps no tick arrays
+    #[account(mut)]
+    pub tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: The stub keeps no tick arrays
+    #[account(mut)]
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    #[account(mut, token::mint = token_vault_0.mint)]
+    pub recipient_token_account_0: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = token_vault_1.mint)]
+    pub recipient_token_account_1: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    /// CHECK: Only classic SPL mints reach the stub
+    pub token_program_2022: UncheckedAccount<'info>,
+    /// CHECK: The stub writes no memo
+    pub memo_program: UncheckedAccount<'info>,
+    /// CHECK: Mint of vault 0
+    pub vault_0_mint: UncheckedAccount<'info>,
+    /// CHECK: Mint of vault 1
+    pub vault_1_mint: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AccrueFees<'info> {
+    pub payer: Signer<'info>,
+
+    #[account(mut, token::mint = pool_state.token_mint_0)]
+    pub payer_token_0: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = pool_state.token_mint_1)]
+    pub payer_token_1: Box<Account<'info, TokenAccount>>,
+
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut, address = pool_state.token_vault_0)]
+    pub token_vault_0: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, address = pool_state.token_vault_1)]
+    pub token_vault_1: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+// Same layout as Raydium's AmmConfig, pump-fun deserializes it through the CPI crate
+#[account]
+#[derive(Default)]
+pub struct AmmConfig {
+    pub bump: u8,
+    pub index: u16,
+    pub owner: Pubkey,
+    pub protocol_fee_rate: u32,
+    pub trade_fee_rate: u32,
+    pub tick_spacing: u16,
+    pub fund_fee_rate: u32,
+    pub padding_u32: u32,
+    pub fund_owner: Pubkey,
+    pub padding: [u64; 3],
+}
+
+impl AmmConfig {
+    pub const LEN: usize = 8 + 1 + 2 + 32 + 4 + 4 + 2 + 4 + 4 + 32 + 8 * 3;
+}
+
+#[account]
+#[derive(Default)]
+pub struct PoolState {
+    pub bump: u8,
+    pub amm_config: Pubkey,
+    pub token_mint_0: Pubkey,
+    pub token_mint_1: Pubkey,
+    pub token_vault_0: Pubkey,
+    pub token_vault_1: Pubkey,
+    pub sqrt_price_x64: u128,
+    pub liquidity: u128,
+    pub open_time: u64,
+}
+
+impl PoolState {
+    pub const LEN: usize = 8 + 1 + 32 * 5 + 16 + 16 + 8;
+}
+
+#[account]
+#[derive(Default)]
+pub struct PersonalPositionState {
+    pub bump: u8,
+    pub nft_mint: Pubkey,
+    pub pool_id: Pubkey,
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    pub liquidity: u128,
+    pub token_fees_owed_0: u64,
+    pub token_fees_owed_1: u64,
+}
+
+impl PersonalPositionState {
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 4 + 4 + 16 + 8 + 8;
+}
+
+#[error_code]
+pub enum StubError {
+    #[msg("Pool mints must be sorted")]
+    UnsortedMints,
+    #[msg("Tick range is empty")]
+    InvalidTickRange,
+    #[msg("Position needs liquidity")]
+    ZeroLiquidity,
+    #[msg("Only fee collection is supported")]
+    LiquidityRemovalUnsupported,
+    #[msg("Collected fees below the minimum")]
+    PriceSlippageCheck,
+    #[msg("Math overflow")]
+    MathOverflow,
+}
@@ -0,0 +1,298 @@
+// Local stand-in for Raydium CPMM, loaded at the Raydium address by the test validator.
+// Instruction names, arguments and account order match what pump-fun calls through the
+// raydium-cp-swap CPI crate; only pool creation is modelled.
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, spl_token::native_mint, Mint, MintTo, SyncNative, Token, TokenAccount, Transfer},
+};
+
+declare_id!("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
+
+pub const AUTH_SEED: &str = "vault_and_lp_mint_auth_seed";
+pub const AMM_CONFIG_SEED: &str = "amm_config";
+pub const POOL_SEED: &str = "pool";
+pub const POOL_LP_MINT_SEED: &str = "pool_lp_mint";
+pub const POOL_VAULT_SEED: &str = "pool_vault";
+pub const OBSERVATION_SEED: &str = "observation";
+
+// LP kept out of circulation forever, as Raydium does
+pub const LOCK_LP_AMOUNT: u64 = 100;
+
+#[program]
+pub mod raydium_cpmm_stub {
+    use super::*;
+
+    pub fn create_amm_config(
+        ctx: Context<CreateAmmConfig>,
+        index: u16,
+        trade_fee_rate: u64,
+        protocol_fee_rate: u64,
+        fund_fee_rate: u64,
+        create_pool_fee: u64,
+    ) -> Result<()> {
+        let amm_config = &mut ctx.accounts.amm_config;
+        amm_config.bump = ctx.bumps.amm_config;
+        amm_config.index = index;
+        amm_config.trade_fee_rate = trade_fee_rate;
+        amm_config.protocol_fee_rate = protocol_fee_rate;
+        amm_config.fund_fee_rate = fund_fee_rate;
+        amm_config.create_pool_fee = create_pool_fee;
+        amm_config.protocol_owner = ctx.accounts.owner.key();
+        amm_config.fund_owner = ctx.accounts.owner.key();
+        Ok(())
+    }
+
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        init_amount_0: u64,
+        init_amount_1: u64,
+        open_time: u64,
+    ) -> Result<()> {
+        require!(!ctx.accounts.amm_config.disable_create_pool, StubError::NotApproved);
+
+        for (from, to, amount) in [
+            (&ctx.accounts.creator_token_0, &ctx.accounts.token_0_vault, init_amount_0),
+            (&ctx.accounts.creator_token_1, &ctx.accounts.token_1_vault, init_amount_1),
+        ] {
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: from.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: ctx.accounts.creator.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+        }
+
+        let liquidity = isqrt(init_amount_0 as u128 * init_amount_1 as u128) as u64;
+        let lp_amount = liquidity
+            .checked_sub(LOCK_LP_AMOUNT)
+            .ok_or(StubError::InitLpAmountTooLess)?;
+
+        let auth_seeds: &[&[u8]] = &[AUTH_SEED.as_bytes(), &[ctx.bumps.authority]];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    to: ctx.accounts.creator_lp_token.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+                &[auth_seeds],
+            ),
+            lp_amount,
+        )?;
+
+        // Creation fee is paid in SOL into a WSOL account
+        let create_pool_fee = ctx.accounts.amm_config.create_pool_fee;
+        if create_pool_fee > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.creator.to_account_info(),
+                        to: ctx.accounts.create_pool_fee.to_account_info(),
+                    },
+                ),
+                create_pool_fee,
+            )?;
+            token::sync_native(CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                SyncNative {
+                    account: ctx.accounts.create_pool_fee.to_account_info(),
+                },
+            ))?;
+        }
+
+        let pool_state = &mut ctx.accounts.pool_state;
+        pool_state.amm_config = ctx.accounts.amm_config.key();
+        pool_state.pool_creator = ctx.accounts.creator.key();
+        pool_state.token_0_vault = ctx.accounts.token_0_vault.key();
+        pool_state.token_1_vault = ctx.accounts.token_1_vault.key();
+        pool_state.lp_mint = ctx.accounts.lp_mint.key();
+        pool_state.token_0_mint = ctx.accounts.token_0_mint.key();
+        pool_state.token_1_mint = ctx.accounts.token_1_mint.key();
+        pool_state.lp_supply = liquidity;
+        pool_state.open_time = open_time;
+        Ok(())
+    }
+}
+
+fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+#[derive(Accounts)]
+#[instruction(index: u16)]
+pub struct CreateAmmConfig<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        seeds = [AMM_CONFIG_SEED.as_bytes(), &index.to_be_bytes()],
+        bump,
+        payer = owner,
+        space = AmmConfig::LEN,
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// CHECK: Vault and LP mint authority
+    #[account(seeds = [AUTH_SEED.as_bytes()], bump)]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        seeds = [
+            POOL_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+            token_0_mint.key().as_ref(),
+            token_1_mint.key().as_ref(),
+        ],
+        bump,
+        payer = creator,
+        space = PoolState::LEN,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    #[account(constraint = token_0_mint.key() < token_1_mint.key() @ StubError::UnsortedMints)]
+    pub token_0_mint: Box<Account<'info, Mint>>,
+
+    pub token_1_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        seeds = [POOL_LP_MINT_SEED.as_bytes(), pool_state.key().as_ref()],
+        bump,
+        mint::decimals = 9,
+        mint::authority = authority,
+        payer = creator,
+    )]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut, token::mint = token_0_mint, token::authority = creator)]
+    pub creator_token_0: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = token_1_mint, token::authority = creator)]
+    pub creator_token_1: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        associated_token::mint = lp_mint,
+        associated_token::authority = creator,
+        payer = creator,
+    )]
+    pub creator_lp_token: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        seeds = [POOL_VAULT_SEED.as_bytes(), pool_state.key().as_ref(), token_0_mint.key().as_ref()],
+        bump,
+        payer = creator,
+        token::mint = token_0_mint,
+        token::authority = authority,
+        token::token_program = token_0_program,
+    )]
+    pub token_0_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        seeds = [POOL_VAULT_SEED.as_bytes(), pool_state.key().as_ref(), token_1_mint.key().as_ref()],
+        bump,
+        payer = creator,
+        token::mint = token_1_mint,
+        token::authority = authority,
+        token::token_program = token_1_program,
+    )]
+    pub token_1_vault: Box<Account<'info, TokenAccount>>,
+
+    // Raydium pins the receiver address, the stub takes the config owner's WSOL account
+    #[account(
+        mut,
+        token::mint = native_mint::ID,
+        token::authority = amm_config.protocol_owner,
+    )]
+    pub create_pool_fee: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Only its address is checked, the stub keeps no observations
+    #[account(seeds = [OBSERVATION_SEED.as_bytes(), pool_state.key().as_ref()], bump)]
+    pub observation_state: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub token_0_program: Program<'info, Token>,
+    pub token_1_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Same layout as Raydium's AmmConfig, pump-fun deserializes it through the CPI crate
+#[account]
+#[derive(Default)]
+pub struct AmmConfig {
+    pub bump: u8,
+    pub disable_create_pool: bool,
+    pub index: u16,
+    pub trade_fee_rate: u64,
+    pub protocol_fee_rate: u64,
+    pub fund_fee_rate: u64,
+    pub create_pool_fee: u64,
+    pub protocol_owner: Pubkey,
+    pub fund_owner: Pubkey,
+    pub padding: [u64; 16],
+}
+
+impl AmmConfig {
+    pub const LEN: usize = 8 + 1 + 1 + 2 + 8 * 4 + 32 * 2 + 8 * 16;
+}
+
+#[account]
+#[derive(Default)]
+pub struct PoolState {
+    pub amm_config: Pubkey,
+    pub pool_creator: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub lp_mint: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+    pub lp_supply: u64,
+    pub open_time: u64,
+}
+
+impl PoolState {
+    pub const LEN: usize = 8 + 32 * 7 + 8 + 8;
+}
+
+#[error_code]
+pub enum StubError {
+    #[msg("Pool creation is disabled for this config")]
+    NotApproved,
+    #[msg("Pool mints must be sorted")]
+    UnsortedMints,
+    #[msg("Initial liquidity is below the locked amount")]
+    InitLpAmountTooLess,
+}
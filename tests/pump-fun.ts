@@ -1,11 +1,14 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { PumpFun } from "../target/types/pump-fun";
-import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { RaydiumClmmStub } from "../target/types/raydium_clmm_stub";
+import { RaydiumCpmmStub } from "../target/types/raydium_cpmm_stub";
+import { RaydiumAmmV4Stub } from "../target/types/raydium_amm_v4_stub";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, sendAndConfirmTransaction, SystemProgram, Transaction } from "@solana/web3.js";
 import { assert, expect } from "chai";
 import { before } from "mocha";
 import BN from "bn.js";
-import { ASSOCIATED_TOKEN_PROGRAM_ID, createAssociatedTokenAccount, getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { ASSOCIATED_TOKEN_PROGRAM_ID, createAssociatedTokenAccount, createWrappedNativeAccount, getAssociatedTokenAddress, getAssociatedTokenAddressSync, NATIVE_MINT, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, transfer } from "@solana/spl-token";
 
 
 const METADATA_PROGRAM_ID = new PublicKey(
@@ -28,10 +31,15 @@ describe("pump-fun", () => {
   // fees 
   const buyFeePercentage = 5;
   const sellFeePercentage = 5;
-  const curveLimit = new anchor.BN(1000000000);
+  // Well above the initial virtual SOL so the shared curve keeps trading across suites
+  const curveLimit = new anchor.BN(100 * LAMPORTS_PER_SOL);
 
   let configPda: PublicKey;
   let bondingCurvePda: PublicKey;
+  let solVaultPda: PublicKey;
+  let feeStatsPda: PublicKey;
+  let creatorVaultPda: PublicKey;
+  let feeVaultPda: PublicKey;
   let curveTokenAccount: PublicKey;
   let userTokenAccount: PublicKey;
   let tokenMint: Keypair;
@@ -65,6 +73,26 @@ describe("pump-fun", () => {
       program.programId
     );
 
+    [solVaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("curve_sol_vault"), tokenMint.publicKey.toBuffer()],
+      program.programId
+    );
+
+    [creatorVaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("creator_vault"), tokenMint.publicKey.toBuffer()],
+      program.programId
+    );
+
+    [feeStatsPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("fee_stats")],
+      program.programId
+    );
+
+    [feeVaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("fee_vault")],
+      program.programId
+    );
+
     [metadataPda] = PublicKey.findProgramAddressSync(
       [Buffer.from("metadata"), 
         METADATA_PROGRAM_ID.toBuffer(),
@@ -75,6 +103,98 @@ describe("pump-fun", () => {
   });
 
 
+  // Re-run configure with some fields changed, everything else kept as it is on chain
+  const reconfigure = async (overrides: Record<string, unknown>) => {
+    const current = await program.account.config.fetch(configPda);
+    await program.methods
+      .configure({ ...current, ...overrides } as any)
+      .accounts({
+        admin: creator.publicKey,
+        globalConfig: configPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([creator])
+      .rpc();
+  };
+
+  const tradeTrackerPda = (trader: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("trade_tracker"), tokenMint.publicKey.toBuffer(), trader.toBuffer()],
+      program.programId
+    )[0];
+
+  const pda = (seeds: Buffer[], programId = program.programId) =>
+    PublicKey.findProgramAddressSync(seeds, programId)[0];
+
+  // PDAs and token accounts of the curve for a mint
+  const curveAccounts = (mint: PublicKey) => {
+    const bondingCurve = pda([Buffer.from("bonding_curve"), mint.toBuffer()]);
+    return {
+      mint,
+      bondingCurve,
+      solVault: pda([Buffer.from("curve_sol_vault"), mint.toBuffer()]),
+      creatorVault: pda([Buffer.from("creator_vault"), mint.toBuffer()]),
+      curveTokenAccount: getAssociatedTokenAddressSync(mint, bondingCurve, true),
+      positionAuthority: pda([Buffer.from("position_authority"), mint.toBuffer()]),
+    };
+  };
+  type CurveAccounts = ReturnType<typeof curveAccounts>;
+
+  // Launch a fresh curve from the creator under the current config
+  const launchCurve = async (creatorFeeBps = 0, mint = Keypair.generate()) => {
+    const curve = curveAccounts(mint.publicKey);
+    await program.methods
+      .launch(name, symbol, uri, creatorFeeBps)
+      .accountsStrict({
+        creator: creator.publicKey,
+        globalConfig: configPda,
+        tokenMint: mint.publicKey,
+        bondingCurve: curve.bondingCurve,
+        solVault: curve.solVault,
+        creatorVault: curve.creatorVault,
+        feeStats: feeStatsPda,
+        feeVault: feeVaultPda,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        curveTokenAccount: curve.curveTokenAccount,
+        tokenMetadataAccount: pda(
+          [Buffer.from("metadata"), METADATA_PROGRAM_ID.toBuffer(), mint.publicKey.toBuffer()],
+          METADATA_PROGRAM_ID
+        ),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        metadataProgram: METADATA_PROGRAM_ID,
+      })
+      .signers([creator, mint])
+      .rpc();
+    return curve;
+  };
+
+  // Plain SOL swap, on the shared curve unless another mint is given, no WSOL and no referrer
+  const swapAccounts = (trader: PublicKey, mint = tokenMint.publicKey) => {
+    const curve = curveAccounts(mint);
+    return {
+      user: trader,
+      globalConfig: configPda,
+      creatorVault: curve.creatorVault,
+      feeStats: feeStatsPda,
+      feeVault: feeVaultPda,
+      bondingCurve: curve.bondingCurve,
+      solVault: curve.solVault,
+      tokenMint: mint,
+      curveTokenAccount: curve.curveTokenAccount,
+      userTokenAccount: getAssociatedTokenAddressSync(mint, trader),
+      wsolMint: null,
+      userWsolAccount: null,
+      curveWsolAccount: null,
+      referrer: null,
+      tradeTracker: pda([Buffer.from("trade_tracker"), mint.toBuffer(), trader.toBuffer()]),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+  };
+
   it("Can configure", async ()=> {
     const configuration = {
       admin: creator.publicKey,
@@ -96,6 +216,28 @@ describe("pump-fun", () => {
           buyFeePercentage: buyFeePercentage,
           sellFeePercentage: sellFeePercentage,
           migrationFeePercentage: 0,
+          crankFee: new anchor.BN(10000),
+          maxPriceImpactBps: 0,
+          migrationPriceToleranceBps: 500,
+          migrationTarget: { raydiumClmm: {} },
+          migrationBounty: new anchor.BN(0),
+          migrator: PublicKey.default,
+          graduationWindow: new anchor.BN(0),
+          creatorLpFeeBps: 5000,
+          clmmConfigIndex: 0,
+          cpmmConfigIndex: 0,
+          auditPauseThreshold: new anchor.BN(0),
+          breakerWindowSlots: new anchor.BN(0),
+          breakerThresholdBps: 0,
+          sellPenaltyWindowSlots: new anchor.BN(0),
+          sellPenaltyBps: 0,
+          sellPenaltyToCurve: false,
+          feeManager: PublicKey.default,
+          launchFee: new anchor.BN(0),
+          maxCreatorFeeBps: 100,
+          maxReferralShareBps: 2000,
+          feeSplit: [],
+          feeSplitPerTrade: false,
         }).accounts(configuration)
         .signers([creator])
         .rpc();
@@ -129,12 +271,16 @@ describe("pump-fun", () => {
     });
 
     try {
-      const signature = await program.methods.launch(name, symbol, uri)
+      const signature = await program.methods.launch(name, symbol, uri, 0)
         .accountsStrict({
           creator: creator.publicKey,
           globalConfig: configPda,
           tokenMint:  tokenMint.publicKey,
           bondingCurve: bondingCurvePda,
+          solVault: solVaultPda,
+          creatorVault: creatorVaultPda,
+          feeStats: feeStatsPda,
+          feeVault: feeVaultPda,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           curveTokenAccount: curveTokenAccount,
           tokenMetadataAccount: metadataPda,
@@ -195,19 +341,35 @@ describe("pump-fun", () => {
           const tx = await program.methods.swap(
             new anchor.BN(10000),
             0,
-            new anchor.BN(1)
+            new anchor.BN(1),
+            null
           )
           .accountsStrict({
             user: user.publicKey,
             globalConfig: configPda,
-            feeRecipient: creator.publicKey,
+            creatorVault: creatorVaultPda,
+            feeStats: feeStatsPda,
+            feeVault: feeVaultPda,
             bondingCurve: bondingCurvePda,
+            solVault: solVaultPda,
             tokenMint: tokenMint.publicKey,
             curveTokenAccount: curveTokenAccount,
             userTokenAccount: userTokenAccount,
+            tradeTracker: PublicKey.findProgramAddressSync(
+              [
+                Buffer.from("trade_tracker"),
+                tokenMint.publicKey.toBuffer(),
+                user.publicKey.toBuffer(),
+              ],
+              program.programId
+            )[0],
             tokenProgram: TOKEN_PROGRAM_ID,
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-            systemProgram: SystemProgram.programId
+            systemProgram: SystemProgram.programId,
+            wsolMint: null,
+            userWsolAccount: null,
+            curveWsolAccount: null,
+            referrer: null,
           })
           .signers([user])
           .rpc()
@@ -262,7 +424,9 @@ describe("pump-fun", () => {
         const sellConfig = {
           user: user.publicKey,
           globalConfig: configPda,
-          feeRecipient: creator.publicKey,
+          creatorVault: creatorVaultPda,
+          feeStats: feeStatsPda,
+          feeVault: feeVaultPda,
           bondingCurve: bondingCurvePda,
           tokenMint: tokenMint.publicKey,
           curveTokenAccount: curveTokenAccount,
@@ -273,7 +437,7 @@ describe("pump-fun", () => {
         };
         // Get the transaction instruction
         const ix = await program.methods
-          .swap(amount, 1, new anchor.BN(1))
+          .swap(amount, 1, new anchor.BN(1), null)
           .accounts(sellConfig)
           .instruction();
 
@@ -322,7 +486,9 @@ describe("pump-fun", () => {
         const buyConfig = {
           user: user.publicKey,
           globalConfig: configPda,
-          feeRecipient: creator.publicKey,
+          creatorVault: creatorVaultPda,
+          feeStats: feeStatsPda,
+          feeVault: feeVaultPda,
           bondingCurve: bondingCurvePda,
           tokenMint: tokenMint.publicKey,
           curveTokenAccount: curveTokenAccount,
@@ -333,7 +499,7 @@ describe("pump-fun", () => {
         };
 
         await program.methods
-          .swap(largeAmount, 0, new anchor.BN(1))
+          .swap(largeAmount, 0, new anchor.BN(1), null)
           .accounts(buyConfig)
           .signers([user])
           .rpc();
@@ -357,7 +523,9 @@ describe("pump-fun", () => {
         const sellConfig = {
           user: user.publicKey,
           globalConfig: configPda,
-          feeRecipient: creator.publicKey,
+          creatorVault: creatorVaultPda,
+          feeStats: feeStatsPda,
+          feeVault: feeVaultPda,
           bondingCurve: bondingCurvePda,
           tokenMint: tokenMint.publicKey,
           curveTokenAccount: curveTokenAccount,
@@ -368,7 +536,7 @@ describe("pump-fun", () => {
         };
 
         await program.methods
-          .swap(largeAmount, 1, new anchor.BN(1))
+          .swap(largeAmount, 1, new anchor.BN(1), null)
           .accounts(sellConfig)
           .signers([user])
           .rpc();
@@ -380,6 +548,316 @@ describe("pump-fun", () => {
     });
   });
 
+  describe("WSOL swap tests", () => {
+    const wsolTrader = Keypair.generate();
+    let traderTokenAccount: PublicKey;
+    let traderWsolAccount: PublicKey;
+    let curveWsolPda: PublicKey;
+    let traderTrackerPda: PublicKey;
+
+    before(async () => {
+      await provider.connection.requestAirdrop(
+        wsolTrader.publicKey,
+        2 * anchor.web3.LAMPORTS_PER_SOL
+      );
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+
+      traderTokenAccount = await getAssociatedTokenAddress(
+        tokenMint.publicKey,
+        wsolTrader.publicKey
+      );
+      traderWsolAccount = await createWrappedNativeAccount(
+        provider.connection,
+        wsolTrader,
+        wsolTrader.publicKey,
+        100000
+      );
+      [curveWsolPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("curve_wsol"), tokenMint.publicKey.toBuffer()],
+        program.programId
+      );
+      [traderTrackerPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("trade_tracker"),
+          tokenMint.publicKey.toBuffer(),
+          wsolTrader.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    });
+
+    const swapAccounts = (curveWsolAccount: PublicKey | null) => ({
+      user: wsolTrader.publicKey,
+      globalConfig: configPda,
+      creatorVault: creatorVaultPda,
+      feeStats: feeStatsPda,
+      feeVault: feeVaultPda,
+      bondingCurve: bondingCurvePda,
+      solVault: solVaultPda,
+      tokenMint: tokenMint.publicKey,
+      curveTokenAccount: curveTokenAccount,
+      userTokenAccount: traderTokenAccount,
+      wsolMint: NATIVE_MINT,
+      userWsolAccount: traderWsolAccount,
+      curveWsolAccount,
+      referrer: null,
+      tradeTracker: traderTrackerPda,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    });
+
+    it("Can buy with WSOL", async () => {
+      const amount = new anchor.BN(50000);
+
+      await program.methods
+        .swap(amount, 0, new anchor.BN(1), null)
+        .accountsStrict(swapAccounts(curveWsolPda))
+        .signers([wsolTrader])
+        .rpc();
+
+      const wsol = await provider.connection.getTokenAccountBalance(traderWsolAccount);
+      expect(wsol.value.amount).to.equal("50000");
+
+      const tokens = await provider.connection.getTokenAccountBalance(traderTokenAccount);
+      expect(new anchor.BN(tokens.value.amount).gtn(0)).to.be.true;
+
+      // The unwrap account is closed in the same instruction
+      expect(await provider.connection.getAccountInfo(curveWsolPda)).to.be.null;
+    });
+
+    it("Should fail a WSOL sell that passes the curve WSOL account", async () => {
+      try {
+        await program.methods
+          .swap(new anchor.BN(1000), 1, new anchor.BN(1), null)
+          .accountsStrict(swapAccounts(curveWsolPda))
+          .signers([wsolTrader])
+          .rpc();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.error?.errorCode?.code).to.equal("UnexpectedWsolAccount");
+      }
+      expect(await provider.connection.getAccountInfo(curveWsolPda)).to.be.null;
+    });
+
+    it("Can sell for WSOL", async () => {
+      const before = await provider.connection.getTokenAccountBalance(traderWsolAccount);
+
+      await program.methods
+        .swap(new anchor.BN(1000), 1, new anchor.BN(1), null)
+        .accountsStrict(swapAccounts(null))
+        .signers([wsolTrader])
+        .rpc();
+
+      const after = await provider.connection.getTokenAccountBalance(traderWsolAccount);
+      expect(new anchor.BN(after.value.amount).gt(new anchor.BN(before.value.amount))).to.be.true;
+    });
+  });
+
+  describe("Price impact tests", () => {
+    // 0.01 SOL against ~1 SOL of virtual reserve moves the spot price by roughly 1%
+    const largeBuy = new anchor.BN(LAMPORTS_PER_SOL / 100);
+
+    it("Should fail a buy that moves the price past the requested limit", async () => {
+      try {
+        await program.methods
+          .swap(largeBuy, 0, new anchor.BN(1), 1)
+          .accountsStrict(swapAccounts(user.publicKey))
+          .signers([user])
+          .rpc();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.error?.errorCode?.code).to.equal("PriceImpactExceeded");
+      }
+    });
+
+    it("Should hold a requested limit of 0 to the protocol cap", async () => {
+      await reconfigure({ maxPriceImpactBps: 1 });
+
+      try {
+        await program.methods
+          .swap(largeBuy, 0, new anchor.BN(1), 0)
+          .accountsStrict(swapAccounts(user.publicKey))
+          .signers([user])
+          .rpc();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.error?.errorCode?.code).to.equal("PriceImpactExceeded");
+      } finally {
+        await reconfigure({ maxPriceImpactBps: 0 });
+      }
+    });
+  });
+
+  describe("Circuit breaker tests", () => {
+    const buy = (curve: CurveAccounts, lamports: number) =>
+      program.methods
+        .swap(new anchor.BN(lamports), 0, new anchor.BN(1), null)
+        .accountsStrict(swapAccounts(user.publicKey, curve.mint))
+        .signers([user])
+        .rpc();
+
+    it("Should fail configuring a breaker threshold without a window", async () => {
+      try {
+        await reconfigure({
+          breakerWindowSlots: new anchor.BN(0),
+          breakerThresholdBps: 100,
+        });
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.error?.errorCode?.code).to.equal("IncorrectValueRange");
+      }
+    });
+
+    it("Blocks buys once they move the price past the threshold within the window", async () => {
+      // 1% per window, and a window that outlasts the test
+      await reconfigure({
+        breakerWindowSlots: new anchor.BN(1_000_000),
+        breakerThresholdBps: 100,
+      });
+
+      try {
+        const curve = await launchCurve();
+
+        // 0.02 SOL against ~1 SOL of virtual reserve moves the price by several percent
+        await buy(curve, LAMPORTS_PER_SOL / 50);
+        const bondingCurve = await program.account.bondingCurve.fetch(curve.bondingCurve);
+        expect(bondingCurve.breakerTrippedDirection).to.deep.equal({ buy: {} });
+
+        try {
+          await buy(curve, 10000);
+
+          assert.fail("Should have thrown error");
+        } catch (error) {
+          expect(error.error?.errorCode?.code).to.equal("CircuitBreakerTripped");
+        }
+
+        // Selling goes against the move and stays open
+        const tokens = await provider.connection.getTokenAccountBalance(
+          getAssociatedTokenAddressSync(curve.mint, user.publicKey)
+        );
+        await program.methods
+          .swap(new anchor.BN(tokens.value.amount).divn(2), 1, new anchor.BN(1), null)
+          .accountsStrict(swapAccounts(user.publicKey, curve.mint))
+          .signers([user])
+          .rpc();
+      } finally {
+        await reconfigure({
+          breakerWindowSlots: new anchor.BN(0),
+          breakerThresholdBps: 0,
+        });
+      }
+    });
+  });
+
+  describe("Sell penalty tests", () => {
+    it("Charges the penalty on a quick resale and checks slippage on the net proceeds", async () => {
+      // 10% off a sell within a window that outlasts the test, paid to the protocol
+      await reconfigure({
+        sellPenaltyWindowSlots: new anchor.BN(1_000_000),
+        sellPenaltyBps: 1000,
+        sellPenaltyToCurve: false,
+      });
+
+      try {
+        const curve = await launchCurve();
+        await program.methods
+          .swap(new anchor.BN(LAMPORTS_PER_SOL / 100), 0, new anchor.BN(1), null)
+          .accountsStrict(swapAccounts(user.publicKey, curve.mint))
+          .signers([user])
+          .rpc();
+
+        const tokens = await provider.connection.getTokenAccountBalance(
+          getAssociatedTokenAddressSync(curve.mint, user.publicKey)
+        );
+        const sell = (minOut: anchor.BN) =>
+          program.methods
+            .swap(new anchor.BN(tokens.value.amount), 1, minOut, null)
+            .accountsStrict(swapAccounts(user.publicKey, curve.mint))
+            .signers([user]);
+
+        const { events } = await sell(new anchor.BN(1)).simulate();
+        const sold = events.find((event) => event.data.penaltyAmount !== undefined).data as any;
+        // The fee is a cut of the SOL out, the event's tokenAmount
+        expect(sold.feeAmount.toString()).to.equal(
+          sold.tokenAmount.muln(sellFeePercentage).divn(100).toString()
+        );
+        const afterFee = sold.tokenAmount.sub(sold.feeAmount);
+        expect(sold.penaltyAmount.gtn(0)).to.be.true;
+        expect(sold.penaltyAmount.toString()).to.equal(afterFee.muln(1000).divn(10000).toString());
+        const sellerAmount = afterFee.sub(sold.creatorFee).sub(sold.penaltyAmount);
+
+        // One lamport above the net proceeds is too much, even though the gross output covers it
+        try {
+          await sell(sellerAmount.addn(1)).rpc();
+
+          assert.fail("Should have thrown error");
+        } catch (error) {
+          expect(error.error?.errorCode?.code).to.equal("InsufficientAmountOut");
+        }
+
+        // The penalty goes to the protocol along with the regular fee
+        const feeVaultBefore = await provider.connection.getBalance(feeVaultPda);
+        await sell(sellerAmount).rpc();
+        const feeVaultAfter = await provider.connection.getBalance(feeVaultPda);
+        expect(feeVaultAfter - feeVaultBefore).to.equal(
+          sold.feeAmount.add(sold.penaltyAmount).toNumber()
+        );
+      } finally {
+        await reconfigure({
+          sellPenaltyWindowSlots: new anchor.BN(0),
+          sellPenaltyBps: 0,
+        });
+      }
+    });
+
+    it("Lets a trader close their trade tracker once no penalty hangs on it", async () => {
+      const closeTracker = () =>
+        program.methods
+          .closeTradeTracker()
+          .accountsStrict({
+            owner: user.publicKey,
+            globalConfig: configPda,
+            tokenMint: tokenMint.publicKey,
+            tradeTracker: tradeTrackerPda(user.publicKey),
+          })
+          .signers([user])
+          .rpc();
+
+      await reconfigure({ sellPenaltyWindowSlots: new anchor.BN(1_000_000), sellPenaltyBps: 1000 });
+      try {
+        await program.methods
+          .swap(new anchor.BN(1_000_000), 0, new anchor.BN(1), null)
+          .accountsStrict(swapAccounts(user.publicKey))
+          .signers([user])
+          .rpc();
+
+        // Closing now would wipe the buy the penalty is charged against
+        try {
+          await closeTracker();
+
+          assert.fail("Should have thrown error");
+        } catch (error) {
+          expect(error.error?.errorCode?.code).to.equal("SellPenaltyActive");
+        }
+      } finally {
+        await reconfigure({ sellPenaltyWindowSlots: new anchor.BN(0), sellPenaltyBps: 0 });
+      }
+
+      const rent = await provider.connection.getBalance(tradeTrackerPda(user.publicKey));
+      const userBefore = await provider.connection.getBalance(user.publicKey);
+      await closeTracker();
+      const userAfter = await provider.connection.getBalance(user.publicKey);
+
+      expect(await provider.connection.getAccountInfo(tradeTrackerPda(user.publicKey))).to.be.null;
+      expect(userAfter - userBefore).to.equal(rent);
+    });
+  });
+
   describe("Configuration tests", () => {
     it("Should fail configure with invalid fee percentages", async () => {
       const newUser = Keypair.generate();
@@ -406,6 +884,28 @@ describe("pump-fun", () => {
           buyFeePercentage: 101, // Invalid percentage
           sellFeePercentage: 101, // Invalid percentage
           migrationFeePercentage: 0,
+          crankFee: new anchor.BN(10000),
+          maxPriceImpactBps: 0,
+          migrationPriceToleranceBps: 500,
+          migrationTarget: { raydiumClmm: {} },
+          migrationBounty: new anchor.BN(0),
+          migrator: PublicKey.default,
+          graduationWindow: new anchor.BN(0),
+          creatorLpFeeBps: 5000,
+          clmmConfigIndex: 0,
+          cpmmConfigIndex: 0,
+          auditPauseThreshold: new anchor.BN(0),
+          breakerWindowSlots: new anchor.BN(0),
+          breakerThresholdBps: 0,
+          sellPenaltyWindowSlots: new anchor.BN(0),
+          sellPenaltyBps: 0,
+          sellPenaltyToCurve: false,
+          feeManager: PublicKey.default,
+          launchFee: new anchor.BN(0),
+          maxCreatorFeeBps: 100,
+          maxReferralShareBps: 2000,
+          feeSplit: [],
+          feeSplitPerTrade: false,
           reserved: Array(8).fill(Array(8).fill(0)),
         };
 
@@ -421,6 +921,17 @@ describe("pump-fun", () => {
       }
     });
 
+    it("Should fail configure with a migration price tolerance below the migration fee", async () => {
+      // A 2% fee opens a 200 bps gap between the pool and the curve, more than 100 bps allows
+      try {
+        await reconfigure({ migrationFeePercentage: 2, migrationPriceToleranceBps: 100 });
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.error?.errorCode?.code).to.equal("ToleranceBelowMigrationFee");
+      }
+    });
+
     it("Should fail configure with unauthorized user", async () => {
       const unauthorizedUser = Keypair.generate();
       await provider.connection.requestAirdrop(
@@ -446,6 +957,28 @@ describe("pump-fun", () => {
           buyFeePercentage: 5,
           sellFeePercentage: 5,
           migrationFeePercentage: 0,
+          crankFee: new anchor.BN(10000),
+          maxPriceImpactBps: 0,
+          migrationPriceToleranceBps: 500,
+          migrationTarget: { raydiumClmm: {} },
+          migrationBounty: new anchor.BN(0),
+          migrator: PublicKey.default,
+          graduationWindow: new anchor.BN(0),
+          creatorLpFeeBps: 5000,
+          clmmConfigIndex: 0,
+          cpmmConfigIndex: 0,
+          auditPauseThreshold: new anchor.BN(0),
+          breakerWindowSlots: new anchor.BN(0),
+          breakerThresholdBps: 0,
+          sellPenaltyWindowSlots: new anchor.BN(0),
+          sellPenaltyBps: 0,
+          sellPenaltyToCurve: false,
+          feeManager: PublicKey.default,
+          launchFee: new anchor.BN(0),
+          maxCreatorFeeBps: 100,
+          maxReferralShareBps: 2000,
+          feeSplit: [],
+          feeSplitPerTrade: false,
           reserved: Array(8).fill(Array(8).fill(0)),
         };
 
@@ -460,22 +993,124 @@ describe("pump-fun", () => {
         expect(error).to.exist;
       }
     });
+    it("Keeps the SOL vault at the real reserve plus rent", async () => {
+      const bondingCurve = await program.account.bondingCurve.fetch(
+        bondingCurvePda
+      );
+      const vaultBalance = await provider.connection.getBalance(solVaultPda);
+      const rent = await provider.connection.getMinimumBalanceForRentExemption(0);
+
+      expect(vaultBalance).to.equal(bondingCurve.realSolReserve.toNumber() + rent);
+    });
+
+    it("Keeps trading after a SOL donation to the vault", async () => {
+      const donation = LAMPORTS_PER_SOL / 100;
+      await sendAndConfirmTransaction(
+        provider.connection,
+        new Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: user.publicKey,
+            toPubkey: solVaultPda,
+            lamports: donation,
+          })
+        ),
+        [user]
+      );
+
+      await program.methods
+        .swap(new anchor.BN(10000), 0, new anchor.BN(1), null)
+        .accountsStrict(swapAccounts(user.publicKey))
+        .signers([user])
+        .rpc();
+
+      // The donation sits on top of the reserve, it is never counted as curve SOL
+      const bondingCurve = await program.account.bondingCurve.fetch(bondingCurvePda);
+      const vaultBalance = await provider.connection.getBalance(solVaultPda);
+      const rent = await provider.connection.getMinimumBalanceForRentExemption(0);
+      expect(vaultBalance).to.equal(bondingCurve.realSolReserve.toNumber() + rent + donation);
+    });
   });
   
-  describe("Migration tests", () => {
-    it("Should fail migrate when curve is not completed", async () => {
-      try {
-        const migrateConfig = {
-          authority: creator.publicKey,
+  describe("Limit order tests", () => {
+    const orderId = new anchor.BN(1);
+    let limitOrderPda: PublicKey;
+    let orderTokenAccount: PublicKey;
+
+    before(async () => {
+      [limitOrderPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("limit_order"),
+          tokenMint.publicKey.toBuffer(),
+          user.publicKey.toBuffer(),
+          orderId.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+      orderTokenAccount = await getAssociatedTokenAddress(
+        tokenMint.publicKey,
+        limitOrderPda,
+        true
+      );
+    });
+
+    it("Can place a buy limit order", async () => {
+      const userTokenAccount = await getAssociatedTokenAddress(
+        tokenMint.publicKey,
+        user.publicKey
+      );
+      const expiresAt = new anchor.BN(Math.floor(Date.now() / 1000) + 3600);
+
+      await program.methods
+        .placeOrder(orderId, 0, new anchor.BN(10000), new anchor.BN(1), new anchor.BN(1), expiresAt)
+        .accountsStrict({
+          owner: user.publicKey,
           globalConfig: configPda,
           bondingCurve: bondingCurvePda,
           tokenMint: tokenMint.publicKey,
+          limitOrder: limitOrderPda,
+          orderTokenAccount: orderTokenAccount,
+          ownerTokenAccount: userTokenAccount,
+          referrer: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
-        };
+        })
+        .signers([user])
+        .rpc();
+
+      const order = await program.account.limitOrder.fetch(limitOrderPda);
+      expect(order.owner.toBase58()).to.equal(user.publicKey.toBase58());
+      expect(order.amount.toString()).to.equal("10000");
+    });
+
+    it("Should fail fill when price has not crossed the limit", async () => {
+      const userTokenAccount = await getAssociatedTokenAddress(
+        tokenMint.publicKey,
+        user.publicKey
+      );
 
+      try {
         await program.methods
-          .migrate()
-          .accounts(migrateConfig)
+          .fillOrder()
+          .accountsStrict({
+            cranker: creator.publicKey,
+            globalConfig: configPda,
+            creatorVault: creatorVaultPda,
+            feeStats: feeStatsPda,
+            feeVault: feeVaultPda,
+            bondingCurve: bondingCurvePda,
+            solVault: solVaultPda,
+            tokenMint: tokenMint.publicKey,
+            curveTokenAccount: curveTokenAccount,
+            limitOrder: limitOrderPda,
+            orderTokenAccount: orderTokenAccount,
+            owner: user.publicKey,
+            ownerTokenAccount: userTokenAccount,
+            tradeTracker: tradeTrackerPda(user.publicKey),
+            referrer: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
           .signers([creator])
           .rpc();
 
@@ -485,33 +1120,2331 @@ describe("pump-fun", () => {
       }
     });
 
-    it("Should fail migrate with unauthorized user", async () => {
-      const unauthorizedUser = Keypair.generate();
-      await provider.connection.requestAirdrop(
-        unauthorizedUser.publicKey,
-        anchor.web3.LAMPORTS_PER_SOL
+    it("Can cancel a limit order", async () => {
+      const userTokenAccount = await getAssociatedTokenAddress(
+        tokenMint.publicKey,
+        user.publicKey
       );
 
-      try {
-        const migrateConfig = {
-          authority: unauthorizedUser.publicKey,
+      // Dust sent to the escrow's known address must not keep it from closing
+      await transfer(provider.connection, user, userTokenAccount, orderTokenAccount, user, 1);
+      const tokensBefore = await provider.connection.getTokenAccountBalance(userTokenAccount);
+
+      await program.methods
+        .cancelOrder()
+        .accountsStrict({
+          canceller: user.publicKey,
+          bondingCurve: bondingCurvePda,
+          tokenMint: tokenMint.publicKey,
+          limitOrder: limitOrderPda,
+          orderTokenAccount: orderTokenAccount,
+          owner: user.publicKey,
+          ownerTokenAccount: userTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([user])
+        .rpc();
+
+      const order = await provider.connection.getAccountInfo(limitOrderPda);
+      expect(order).to.be.null;
+      expect(await provider.connection.getAccountInfo(orderTokenAccount)).to.be.null;
+
+      const tokensAfter = await provider.connection.getTokenAccountBalance(userTokenAccount);
+      expect(Number(tokensAfter.value.amount) - Number(tokensBefore.value.amount)).to.equal(1);
+    });
+  });
+
+  describe("Trigger order tests", () => {
+    const orderId = new anchor.BN(1);
+    const amount = new anchor.BN(1000);
+    let triggerOrderPda: PublicKey;
+    let orderTokenAccount: PublicKey;
+
+    before(async () => {
+      [triggerOrderPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("trigger_order"),
+          tokenMint.publicKey.toBuffer(),
+          user.publicKey.toBuffer(),
+          orderId.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+      orderTokenAccount = await getAssociatedTokenAddress(
+        tokenMint.publicKey,
+        triggerOrderPda,
+        true
+      );
+    });
+
+    it("Can place a trigger order", async () => {
+      const userTokenAccount = await getAssociatedTokenAddress(
+        tokenMint.publicKey,
+        user.publicKey
+      );
+
+      // Stop far below and take profit far above spot, so neither side fires yet
+      await program.methods
+        .placeTriggerOrder(orderId, amount, new anchor.BN(1), new anchor.BN("1000000000000000000"), new anchor.BN(1))
+        .accountsStrict({
+          owner: user.publicKey,
           globalConfig: configPda,
           bondingCurve: bondingCurvePda,
           tokenMint: tokenMint.publicKey,
+          triggerOrder: triggerOrderPda,
+          orderTokenAccount: orderTokenAccount,
+          ownerTokenAccount: userTokenAccount,
+          referrer: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
-        };
+        })
+        .signers([user])
+        .rpc();
 
-        await program.methods
-          .migrate()
-          .accounts(migrateConfig)
-          .signers([unauthorizedUser])
-          .rpc();
+      const order = await program.account.triggerOrder.fetch(triggerOrderPda);
+      expect(order.owner.toBase58()).to.equal(user.publicKey.toBase58());
+      expect(order.amount.toString()).to.equal(amount.toString());
+
+      const escrow = await provider.connection.getTokenAccountBalance(orderTokenAccount);
+      expect(escrow.value.amount).to.equal(amount.toString());
+    });
+
+    it("Should fail execute when neither trigger price is hit", async () => {
+      try {
+        await program.methods
+          .executeTriggerOrder()
+          .accountsStrict({
+            cranker: creator.publicKey,
+            globalConfig: configPda,
+            creatorVault: creatorVaultPda,
+            feeStats: feeStatsPda,
+            feeVault: feeVaultPda,
+            bondingCurve: bondingCurvePda,
+            solVault: solVaultPda,
+            tokenMint: tokenMint.publicKey,
+            curveTokenAccount: curveTokenAccount,
+            triggerOrder: triggerOrderPda,
+            orderTokenAccount: orderTokenAccount,
+            owner: user.publicKey,
+            ownerTokenAccount: getAssociatedTokenAddressSync(tokenMint.publicKey, user.publicKey),
+            tradeTracker: tradeTrackerPda(user.publicKey),
+            referrer: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.error?.errorCode?.code).to.equal("OrderNotTriggered");
+      }
+    });
+
+    it("Should fail cancel by someone other than the owner while the curve trades", async () => {
+      const userTokenAccount = await getAssociatedTokenAddress(
+        tokenMint.publicKey,
+        user.publicKey
+      );
+
+      try {
+        await program.methods
+          .cancelTriggerOrder()
+          .accountsStrict({
+            canceller: creator.publicKey,
+            bondingCurve: bondingCurvePda,
+            tokenMint: tokenMint.publicKey,
+            triggerOrder: triggerOrderPda,
+            orderTokenAccount: orderTokenAccount,
+            owner: user.publicKey,
+            ownerTokenAccount: userTokenAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([creator])
+          .rpc();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.error?.errorCode?.code).to.equal("OrderNotCancellable");
+      }
+    });
+
+    it("Can cancel a trigger order", async () => {
+      const userTokenAccount = await getAssociatedTokenAddress(
+        tokenMint.publicKey,
+        user.publicKey
+      );
+      const before = await provider.connection.getTokenAccountBalance(userTokenAccount);
+
+      await program.methods
+        .cancelTriggerOrder()
+        .accountsStrict({
+          canceller: user.publicKey,
+          bondingCurve: bondingCurvePda,
+          tokenMint: tokenMint.publicKey,
+          triggerOrder: triggerOrderPda,
+          orderTokenAccount: orderTokenAccount,
+          owner: user.publicKey,
+          ownerTokenAccount: userTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([user])
+        .rpc();
+
+      const after = await provider.connection.getTokenAccountBalance(userTokenAccount);
+      expect(new anchor.BN(after.value.amount).sub(new anchor.BN(before.value.amount)).toString())
+        .to.equal(amount.toString());
+      expect(await provider.connection.getAccountInfo(triggerOrderPda)).to.be.null;
+      expect(await provider.connection.getAccountInfo(orderTokenAccount)).to.be.null;
+    });
+
+    describe("Execution", () => {
+      const crankFee = 10000;
+      const userTokenAccount = () =>
+        getAssociatedTokenAddressSync(tokenMint.publicKey, user.publicKey);
+      const orderAccounts = (id: anchor.BN) => {
+        const triggerOrder = pda([
+          Buffer.from("trigger_order"),
+          tokenMint.publicKey.toBuffer(),
+          user.publicKey.toBuffer(),
+          id.toArrayLike(Buffer, "le", 8),
+        ]);
+        return {
+          triggerOrder,
+          orderTokenAccount: getAssociatedTokenAddressSync(tokenMint.publicKey, triggerOrder, true),
+        };
+      };
+
+      const place = (id: anchor.BN, stopPrice: anchor.BN, takeProfitPrice: anchor.BN, minOut: anchor.BN) =>
+        program.methods
+          .placeTriggerOrder(id, amount, stopPrice, takeProfitPrice, minOut)
+          .accountsStrict({
+            owner: user.publicKey,
+            globalConfig: configPda,
+            bondingCurve: bondingCurvePda,
+            tokenMint: tokenMint.publicKey,
+            ...orderAccounts(id),
+            ownerTokenAccount: userTokenAccount(),
+            referrer: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user])
+          .rpc();
+
+      const execute = (id: anchor.BN) =>
+        program.methods
+          .executeTriggerOrder()
+          .accountsStrict({
+            cranker: creator.publicKey,
+            globalConfig: configPda,
+            creatorVault: creatorVaultPda,
+            feeStats: feeStatsPda,
+            feeVault: feeVaultPda,
+            bondingCurve: bondingCurvePda,
+            solVault: solVaultPda,
+            tokenMint: tokenMint.publicKey,
+            curveTokenAccount: curveTokenAccount,
+            ...orderAccounts(id),
+            owner: user.publicKey,
+            ownerTokenAccount: userTokenAccount(),
+            tradeTracker: tradeTrackerPda(user.publicKey),
+            referrer: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator]);
+
+      // Executes the order and checks the owner gets the sale proceeds, the escrow rent and
+      // any leftover tokens, and the cranker the crank fee
+      const executeAndCheck = async (id: anchor.BN, leftoverTokens: number) => {
+        const { triggerOrder, orderTokenAccount } = orderAccounts(id);
+        const { events } = await execute(id).simulate();
+        const sold = events.find((event) => event.data.penaltyAmount !== undefined).data as any;
+        const sellerAmount = sold.tokenAmount
+          .sub(sold.feeAmount)
+          .sub(sold.creatorFee)
+          .sub(sold.penaltyAmount)
+          .toNumber();
+
+        const escrowLamports =
+          (await provider.connection.getBalance(triggerOrder)) +
+          (await provider.connection.getBalance(orderTokenAccount));
+        const ownerBefore = await provider.connection.getBalance(user.publicKey);
+        const crankerBefore = await provider.connection.getBalance(creator.publicKey);
+        const tokensBefore = await provider.connection.getTokenAccountBalance(userTokenAccount());
+
+        await execute(id).rpc();
+
+        expect(await provider.connection.getBalance(user.publicKey)).to.equal(
+          ownerBefore + escrowLamports - crankFee + sellerAmount
+        );
+        expect(await provider.connection.getBalance(creator.publicKey)).to.equal(crankerBefore + crankFee);
+        const tokensAfter = await provider.connection.getTokenAccountBalance(userTokenAccount());
+        expect(Number(tokensAfter.value.amount) - Number(tokensBefore.value.amount)).to.equal(leftoverTokens);
+        expect(await provider.connection.getAccountInfo(triggerOrder)).to.be.null;
+        expect(await provider.connection.getAccountInfo(orderTokenAccount)).to.be.null;
+        return sellerAmount;
+      };
+
+      it("Executes a stop loss once spot is at or below the stop price", async () => {
+        const id = new anchor.BN(2);
+        // Stop far above spot so it has already fired
+        await place(id, new anchor.BN("1000000000000000000"), new anchor.BN(0), new anchor.BN(1));
+
+        // Dust sent to the escrow goes back to the owner instead of blocking the close
+        await transfer(provider.connection, user, userTokenAccount(), orderAccounts(id).orderTokenAccount, user, 1);
+
+        const sellerAmount = await executeAndCheck(id, 1);
+        expect(sellerAmount).to.be.greaterThan(0);
+      });
+
+      it("Executes a take profit once spot is at or above the target", async () => {
+        const id = new anchor.BN(3);
+        // Target below spot so it has already been reached, and a stop that has not
+        await place(id, new anchor.BN(0), new anchor.BN(1), new anchor.BN(1));
+
+        await executeAndCheck(id, 0);
+      });
+
+      it("Should fail executing when the proceeds fall short of the minimum", async () => {
+        const id = new anchor.BN(4);
+        await place(id, new anchor.BN(0), new anchor.BN(1), new anchor.BN("1000000000000000"));
+
+        try {
+          await execute(id).rpc();
+
+          assert.fail("Should have thrown error");
+        } catch (error) {
+          expect(error.error?.errorCode?.code).to.equal("InsufficientAmountOut");
+        }
+
+        // Still open, the owner takes the tokens back
+        const order = await program.account.triggerOrder.fetch(orderAccounts(id).triggerOrder);
+        expect(order.amount.toString()).to.equal(amount.toString());
+        await program.methods
+          .cancelTriggerOrder()
+          .accountsStrict({
+            canceller: user.publicKey,
+            bondingCurve: bondingCurvePda,
+            tokenMint: tokenMint.publicKey,
+            ...orderAccounts(id),
+            owner: user.publicKey,
+            ownerTokenAccount: userTokenAccount(),
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([user])
+          .rpc();
+      });
+    });
+  });
+
+  describe("DCA tests", () => {
+    const dcaId = new anchor.BN(1);
+    let dcaOrderPda: PublicKey;
+
+    before(() => {
+      [dcaOrderPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("dca_order"),
+          tokenMint.publicKey.toBuffer(),
+          user.publicKey.toBuffer(),
+          dcaId.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+    });
+
+    it("Can create a DCA schedule", async () => {
+      const userTokenAccount = await getAssociatedTokenAddress(
+        tokenMint.publicKey,
+        user.publicKey
+      );
+
+      await program.methods
+        .createDca(dcaId, new anchor.BN(10000), new anchor.BN(3), new anchor.BN(60), new anchor.BN(1))
+        .accountsStrict({
+          owner: user.publicKey,
+          globalConfig: configPda,
+          bondingCurve: bondingCurvePda,
+          tokenMint: tokenMint.publicKey,
+          dcaOrder: dcaOrderPda,
+          ownerTokenAccount: userTokenAccount,
+          referrer: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+
+      const dcaOrder = await program.account.dcaOrder.fetch(dcaOrderPda);
+      expect(dcaOrder.installmentsLeft.toString()).to.equal("3");
+    });
+
+    it("Should fail cancel by someone other than the owner", async () => {
+      try {
+        await program.methods
+          .cancelDca()
+          .accountsStrict({
+            canceller: creator.publicKey,
+            bondingCurve: bondingCurvePda,
+            tokenMint: tokenMint.publicKey,
+            dcaOrder: dcaOrderPda,
+            owner: user.publicKey,
+          })
+          .signers([creator])
+          .rpc();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error).to.exist;
+      }
+    });
+
+    it("Can cancel a DCA schedule", async () => {
+      // Every installment and its crank fee is still escrowed, all of it goes back with the rent
+      const escrowed = await provider.connection.getBalance(dcaOrderPda);
+      const rent = await provider.connection.getMinimumBalanceForRentExemption(
+        (await provider.connection.getAccountInfo(dcaOrderPda)).data.length
+      );
+      expect(escrowed - rent).to.equal(3 * (10000 + 10000));
+      const ownerBefore = await provider.connection.getBalance(user.publicKey);
+
+      await program.methods
+        .cancelDca()
+        .accountsStrict({
+          canceller: user.publicKey,
+          bondingCurve: bondingCurvePda,
+          tokenMint: tokenMint.publicKey,
+          dcaOrder: dcaOrderPda,
+          owner: user.publicKey,
+        })
+        .signers([user])
+        .rpc();
+
+      const dcaOrder = await provider.connection.getAccountInfo(dcaOrderPda);
+      expect(dcaOrder).to.be.null;
+      const ownerAfter = await provider.connection.getBalance(user.publicKey);
+      expect(ownerAfter - ownerBefore).to.equal(escrowed);
+    });
+
+    describe("Installments", () => {
+      const installment = 10000;
+      const crankFee = 10000;
+      const userTokenAccount = () =>
+        getAssociatedTokenAddressSync(tokenMint.publicKey, user.publicKey);
+      const schedulePda = (id: anchor.BN) =>
+        pda([
+          Buffer.from("dca_order"),
+          tokenMint.publicKey.toBuffer(),
+          user.publicKey.toBuffer(),
+          id.toArrayLike(Buffer, "le", 8),
+        ]);
+
+      const createSchedule = (id: anchor.BN, installments: number, interval: number, minOut: anchor.BN) =>
+        program.methods
+          .createDca(id, new anchor.BN(installment), new anchor.BN(installments), new anchor.BN(interval), minOut)
+          .accountsStrict({
+            owner: user.publicKey,
+            globalConfig: configPda,
+            bondingCurve: bondingCurvePda,
+            tokenMint: tokenMint.publicKey,
+            dcaOrder: schedulePda(id),
+            ownerTokenAccount: userTokenAccount(),
+            referrer: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user])
+          .rpc();
+
+      const executeSchedule = (id: anchor.BN) =>
+        program.methods
+          .executeDca()
+          .accountsStrict({
+            cranker: creator.publicKey,
+            globalConfig: configPda,
+            creatorVault: creatorVaultPda,
+            feeStats: feeStatsPda,
+            feeVault: feeVaultPda,
+            bondingCurve: bondingCurvePda,
+            solVault: solVaultPda,
+            tokenMint: tokenMint.publicKey,
+            curveTokenAccount: curveTokenAccount,
+            dcaOrder: schedulePda(id),
+            owner: user.publicKey,
+            ownerTokenAccount: userTokenAccount(),
+            tradeTracker: tradeTrackerPda(user.publicKey),
+            referrer: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+
+      const tokenBalance = async () =>
+        new anchor.BN((await provider.connection.getTokenAccountBalance(userTokenAccount())).value.amount);
+
+      it("Runs each installment once its interval has passed and closes after the last", async () => {
+        const id = new anchor.BN(2);
+        await createSchedule(id, 2, 2, new anchor.BN(1));
+
+        // First installment is due right away, the cranker earns one crank fee
+        const tokensBefore = await tokenBalance();
+        const crankerBefore = await provider.connection.getBalance(creator.publicKey);
+        const escrowBefore = await provider.connection.getBalance(schedulePda(id));
+        await executeSchedule(id);
+
+        expect((await tokenBalance()).gt(tokensBefore)).to.be.true;
+        expect(await provider.connection.getBalance(creator.publicKey)).to.equal(crankerBefore + crankFee);
+        expect(await provider.connection.getBalance(schedulePda(id))).to.equal(
+          escrowBefore - installment - crankFee
+        );
+        const schedule = await program.account.dcaOrder.fetch(schedulePda(id));
+        expect(schedule.installmentsLeft.toNumber()).to.equal(1);
+
+        // The next one waits for the interval
+        try {
+          await executeSchedule(id);
+
+          assert.fail("Should have thrown error");
+        } catch (error) {
+          expect(error.error?.errorCode?.code).to.equal("DcaNotDue");
+        }
+
+        await new Promise((resolve) => setTimeout(resolve, 3000));
+
+        // The last installment closes the schedule and hands its rent back to the owner
+        const tokensMid = await tokenBalance();
+        const rent = escrowBefore - 2 * (installment + crankFee);
+        const ownerBefore = await provider.connection.getBalance(user.publicKey);
+        await executeSchedule(id);
+
+        expect((await tokenBalance()).gt(tokensMid)).to.be.true;
+        expect(await provider.connection.getAccountInfo(schedulePda(id))).to.be.null;
+        expect(await provider.connection.getBalance(user.publicKey)).to.equal(ownerBefore + rent);
+      });
+
+      it("Should fail an installment below its minimum output", async () => {
+        const id = new anchor.BN(3);
+        await createSchedule(id, 1, 60, new anchor.BN("1000000000000000"));
+
+        try {
+          await executeSchedule(id);
+
+          assert.fail("Should have thrown error");
+        } catch (error) {
+          expect(error.error?.errorCode?.code).to.equal("InsufficientAmountOut");
+        }
+
+        // The schedule is untouched and can still be cancelled for a full refund
+        const schedule = await program.account.dcaOrder.fetch(schedulePda(id));
+        expect(schedule.installmentsLeft.toNumber()).to.equal(1);
+        await program.methods
+          .cancelDca()
+          .accountsStrict({
+            canceller: user.publicKey,
+            bondingCurve: bondingCurvePda,
+            tokenMint: tokenMint.publicKey,
+            dcaOrder: schedulePda(id),
+            owner: user.publicKey,
+          })
+          .signers([user])
+          .rpc();
+      });
+    });
+  });
+
+  describe("Migration tests", () => {
+    const clmmStub = anchor.workspace.raydiumClmmStub as Program<RaydiumClmmStub>;
+    const cpmmStub = anchor.workspace.raydiumCpmmStub as Program<RaydiumCpmmStub>;
+    const ammV4Stub = anchor.workspace.raydiumAmmV4Stub as Program<RaydiumAmmV4Stub>;
+    const OPENBOOK_PROGRAM_ID = new PublicKey("srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX");
+    const MEMO_PROGRAM_ID = new PublicKey("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+    const CPMM_CREATE_POOL_FEE = 0.15 * LAMPORTS_PER_SOL;
+    const AMM_V4_CREATE_POOL_FEE = 0.4 * LAMPORTS_PER_SOL;
+
+    // Buys out every curve below and migrates it
+    const migrator = Keypair.generate();
+    let cpmmPoolFeeAccount: PublicKey;
+
+    type CompletedCurve = CurveAccounts;
+    let clmmCurve: CompletedCurve;
+    let tokenFirstCurve: CompletedCurve;
+    let cpmmCurve: CompletedCurve;
+    let ammV4Curve: CompletedCurve;
+    let ammCurve: CompletedCurve;
+
+    // Raydium accounts of clmmCurve's position, set once it migrates
+    let clmmPosition: {
+      poolState: PublicKey;
+      tokenVault0: PublicKey;
+      tokenVault1: PublicKey;
+      positionNftMint: PublicKey;
+      positionNftAccount: PublicKey;
+      protocolPosition: PublicKey;
+      personalPosition: PublicKey;
+      tickArrayLower: PublicKey;
+      tickArrayUpper: PublicKey;
+    };
+
+    const u16 = (value: number) => {
+      const buffer = Buffer.alloc(2);
+      buffer.writeUInt16BE(value);
+      return buffer;
+    };
+    const i32 = (value: number) => {
+      const buffer = Buffer.alloc(4);
+      buffer.writeInt32BE(value);
+      return buffer;
+    };
+    const sortedMints = (mint: PublicKey) =>
+      Buffer.compare(mint.toBuffer(), NATIVE_MINT.toBuffer()) < 0
+        ? [mint, NATIVE_MINT]
+        : [NATIVE_MINT, mint];
+
+    // Mint keypair ordered against WSOL as asked, Raydium pools put the lower key first
+    const mintSorting = (beforeWsol: boolean) => {
+      for (;;) {
+        const mint = Keypair.generate();
+        if ((Buffer.compare(mint.publicKey.toBuffer(), NATIVE_MINT.toBuffer()) < 0) === beforeWsol) {
+          return mint;
+        }
+      }
+    };
+
+    // Launch a curve for the target and buy it out in a single trade
+    const completedCurve = async (
+      migrationTarget: object,
+      mint = Keypair.generate(),
+      creatorFeeBps = 0
+    ): Promise<CompletedCurve> => {
+      await reconfigure({
+        migrationTarget,
+        curveLimit: new anchor.BN(1.1 * LAMPORTS_PER_SOL),
+      });
+
+      const curve = await launchCurve(creatorFeeBps, mint);
+      await program.methods
+        .swap(new anchor.BN(0.2 * LAMPORTS_PER_SOL), 0, new anchor.BN(1), null)
+        .accountsStrict(swapAccounts(migrator.publicKey, curve.mint))
+        .signers([migrator])
+        .rpc();
+
+      await reconfigure({ migrationTarget: { raydiumClmm: {} }, curveLimit });
+
+      const state = await program.account.bondingCurve.fetch(curve.bondingCurve);
+      expect(state.isCompleted).to.be.true;
+      return curve;
+    };
+
+    // Curve side of every Raydium migration
+    const positionAccounts = (curve: CompletedCurve) => ({
+      migrator: migrator.publicKey,
+      config: configPda,
+      bondingCurve: curve.bondingCurve,
+      tokenMint: curve.mint,
+      wsolMint: NATIVE_MINT,
+      curveTokenAccount: curve.curveTokenAccount,
+      solVault: curve.solVault,
+      feeStats: feeStatsPda,
+      feeVault: feeVaultPda,
+      positionAuthority: curve.positionAuthority,
+      positionWsolAccount: getAssociatedTokenAddressSync(NATIVE_MINT, curve.positionAuthority, true),
+      positionTokenAccount: getAssociatedTokenAddressSync(curve.mint, curve.positionAuthority, true),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    });
+
+    const builtInPoolAccounts = (curve: CompletedCurve) => {
+      const ammPool = pda([Buffer.from("amm_pool"), curve.mint.toBuffer()]);
+      return {
+        migrator: migrator.publicKey,
+        config: configPda,
+        bondingCurve: curve.bondingCurve,
+        tokenMint: curve.mint,
+        curveTokenAccount: curve.curveTokenAccount,
+        solVault: curve.solVault,
+        feeStats: feeStatsPda,
+        feeVault: feeVaultPda,
+        ammPool,
+        ammSolVault: pda([Buffer.from("amm_sol_vault"), curve.mint.toBuffer()]),
+        ammTokenAccount: getAssociatedTokenAddressSync(curve.mint, ammPool, true),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      };
+    };
+
+    before(async () => {
+      await provider.connection.requestAirdrop(migrator.publicKey, 10 * LAMPORTS_PER_SOL);
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+
+      // Raydium configs at index 0, the allowlisted fee tier
+      await clmmStub.methods
+        .createAmmConfig(0, 60, 2500, 120000, 40000)
+        .accountsPartial({
+          owner: creator.publicKey,
+          ammConfig: pda([Buffer.from("amm_config"), u16(0)], clmmStub.programId),
+        })
+        .signers([creator])
+        .rpc();
+      // A second fee tier that the protocol does not allowlist
+      await clmmStub.methods
+        .createAmmConfig(1, 60, 500, 120000, 40000)
+        .accountsPartial({
+          owner: creator.publicKey,
+          ammConfig: pda([Buffer.from("amm_config"), u16(1)], clmmStub.programId),
+        })
+        .signers([creator])
+        .rpc();
+      await cpmmStub.methods
+        .createAmmConfig(
+          0,
+          new anchor.BN(2500),
+          new anchor.BN(120000),
+          new anchor.BN(40000),
+          new anchor.BN(CPMM_CREATE_POOL_FEE)
+        )
+        .accountsPartial({
+          owner: creator.publicKey,
+          ammConfig: pda([Buffer.from("amm_config"), u16(0)], cpmmStub.programId),
+        })
+        .signers([creator])
+        .rpc();
+      await ammV4Stub.methods
+        .createConfig(new anchor.BN(AMM_V4_CREATE_POOL_FEE))
+        .accountsPartial({ admin: creator.publicKey })
+        .signers([creator])
+        .rpc();
+      cpmmPoolFeeAccount = await createWrappedNativeAccount(
+        provider.connection,
+        creator,
+        creator.publicKey,
+        0
+      );
+
+      clmmCurve = await completedCurve({ raydiumClmm: {} }, mintSorting(false));
+      tokenFirstCurve = await completedCurve({ raydiumClmm: {} }, mintSorting(true));
+      cpmmCurve = await completedCurve({ raydiumCpmm: {} });
+      ammV4Curve = await completedCurve({ raydiumAmmV4: {} });
+      // Pays a creator fee so there is something left to claim once it is finalized
+      ammCurve = await completedCurve({ constantProduct: {} }, Keypair.generate(), 100);
+    });
+
+    it("Should fail migrate when curve is not completed", async () => {
+      try {
+        const migrateConfig = {
+          migrator: creator.publicKey,
+          globalConfig: configPda,
+          bondingCurve: bondingCurvePda,
+          tokenMint: tokenMint.publicKey,
+          systemProgram: SystemProgram.programId,
+        };
+
+        await program.methods
+          .migrate()
+          .accounts(migrateConfig)
+          .signers([creator])
+          .rpc();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error).to.exist;
+      }
+    });
+
+    it("Should fail permissionless migrate when curve is not completed", async () => {
+      const unauthorizedUser = Keypair.generate();
+      await provider.connection.requestAirdrop(
+        unauthorizedUser.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+
+      try {
+        const migrateConfig = {
+          migrator: unauthorizedUser.publicKey,
+          globalConfig: configPda,
+          bondingCurve: bondingCurvePda,
+          tokenMint: tokenMint.publicKey,
+          systemProgram: SystemProgram.programId,
+        };
+
+        await program.methods
+          .migrate()
+          .accounts(migrateConfig)
+          .signers([unauthorizedUser])
+          .rpc();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error).to.exist;
+      }
+    });
+
+    it("Should fail migrate into the built-in pool for a CLMM curve", async () => {
+      const bondingCurve = await program.account.bondingCurve.fetch(
+        clmmCurve.bondingCurve
+      );
+      expect(bondingCurve.migrationTarget).to.deep.equal({ raydiumClmm: {} });
+      expect(bondingCurve.isCompleted).to.be.true;
+
+      try {
+        await program.methods
+          .migrateAmm()
+          .accountsPartial(builtInPoolAccounts(clmmCurve))
+          .signers([migrator])
+          .rpc();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.error?.errorCode?.code).to.equal("WrongMigrationTarget");
+      }
+    });
+
+    // Migrate a CLMM curve and check the pool got both reserves, in mint order, at the deposit price
+    // Every account migrate takes for the curve, under the CLMM config at ammConfigIndex
+    const clmmMigrationAccounts = (
+      curve: CompletedCurve,
+      positionNftMint: PublicKey,
+      ammConfigIndex = 0
+    ) => {
+      const ammConfig = pda([Buffer.from("amm_config"), u16(ammConfigIndex)], clmmStub.programId);
+      const [mint0, mint1] = sortedMints(curve.mint);
+      const poolState = pda(
+        [Buffer.from("pool"), ammConfig.toBuffer(), mint0.toBuffer(), mint1.toBuffer()],
+        clmmStub.programId
+      );
+
+      // Full range at tick spacing 60, one tick array of 60 ticks on each end
+      const tickLower = -443580;
+      const tickUpper = 443580;
+      const tickArrayStart = (tick: number) => Math.floor(tick / 3600) * 3600;
+      const position = {
+        poolState,
+        tokenVault0: pda(
+          [Buffer.from("pool_vault"), poolState.toBuffer(), mint0.toBuffer()],
+          clmmStub.programId
+        ),
+        tokenVault1: pda(
+          [Buffer.from("pool_vault"), poolState.toBuffer(), mint1.toBuffer()],
+          clmmStub.programId
+        ),
+        positionNftMint,
+        positionNftAccount: getAssociatedTokenAddressSync(
+          positionNftMint,
+          curve.positionAuthority,
+          true
+        ),
+        protocolPosition: pda(
+          [Buffer.from("position"), poolState.toBuffer(), i32(tickLower), i32(tickUpper)],
+          clmmStub.programId
+        ),
+        personalPosition: pda(
+          [Buffer.from("position"), positionNftMint.toBuffer()],
+          clmmStub.programId
+        ),
+        tickArrayLower: pda(
+          [Buffer.from("tick_array"), poolState.toBuffer(), i32(tickArrayStart(tickLower))],
+          clmmStub.programId
+        ),
+        tickArrayUpper: pda(
+          [Buffer.from("tick_array"), poolState.toBuffer(), i32(tickArrayStart(tickUpper))],
+          clmmStub.programId
+        ),
+      };
+
+      return {
+        position,
+        accounts: {
+          ...positionAccounts(curve),
+          ...position,
+          observationState: pda(
+            [Buffer.from("observation"), poolState.toBuffer()],
+            clmmStub.programId
+          ),
+          tickArrayBitmap: pda(
+            [Buffer.from("pool_tick_array_bitmap_extension"), poolState.toBuffer()],
+            clmmStub.programId
+          ),
+          metadataAccount: pda(
+            [Buffer.from("metadata"), METADATA_PROGRAM_ID.toBuffer(), positionNftMint.toBuffer()],
+            METADATA_PROGRAM_ID
+          ),
+          ammConfig,
+          metadataProgram: METADATA_PROGRAM_ID,
+          raydiumProgram: clmmStub.programId,
+        },
+      };
+    };
+
+    // Migrate clmmCurve with some accounts swapped out and expect the given error
+    const expectMigrateError = async (
+      overrides: Record<string, PublicKey>,
+      code: string,
+      ammConfigIndex = 0
+    ) => {
+      const positionNftMint = Keypair.generate();
+      const { accounts } = clmmMigrationAccounts(
+        clmmCurve,
+        positionNftMint.publicKey,
+        ammConfigIndex
+      );
+
+      try {
+        await program.methods
+          .migrate()
+          .accountsStrict({ ...accounts, ...overrides })
+          .signers([migrator, positionNftMint])
+          .rpc();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.error?.errorCode?.code).to.equal(code);
+      }
+    };
+
+    it("Should fail migrate with a WSOL mint that is not the native mint", async () => {
+      // The position's WSOL account follows the mint so only the mint check can reject it
+      await expectMigrateError(
+        {
+          wsolMint: tokenMint.publicKey,
+          positionWsolAccount: getAssociatedTokenAddressSync(
+            tokenMint.publicKey,
+            clmmCurve.positionAuthority,
+            true
+          ),
+        },
+        "InvalidWsolMint"
+      );
+    });
+
+    it("Should fail migrate under an AMM config that is not allowlisted", async () => {
+      await expectMigrateError({}, "InvalidAmmConfig", 1);
+    });
+
+    it("Should fail migrate with an observation state off the pool", async () => {
+      await expectMigrateError(
+        { observationState: Keypair.generate().publicKey },
+        "InvalidObservationState"
+      );
+    });
+
+    it("Should fail migrate with a fee vault that is not the protocol's", async () => {
+      await expectMigrateError({ feeVault: Keypair.generate().publicKey }, "ConstraintSeeds");
+    });
+
+    // Migrate a CLMM curve and check the pool got both reserves, in mint order, at the deposit price
+    const migrateClmm = async (curve: CompletedCurve) => {
+      const { realSolReserve } = await program.account.bondingCurve.fetch(curve.bondingCurve);
+      const tokenBalance = new anchor.BN(
+        (await provider.connection.getTokenAccountBalance(curve.curveTokenAccount)).value.amount
+      );
+      const positionNftMint = Keypair.generate();
+      const { position, accounts } = clmmMigrationAccounts(curve, positionNftMint.publicKey);
+      const { poolState, tokenVault0, tokenVault1 } = position;
+      const [mint0] = sortedMints(curve.mint);
+
+      // Raydium's deposit is priced off the final curve price, which the virtual reserves keep above the real ones
+      await reconfigure({ migrationPriceToleranceBps: 10000 });
+
+      const preview = await program.methods
+        .previewMigration()
+        .accountsStrict({
+          config: configPda,
+          bondingCurve: curve.bondingCurve,
+          tokenMint: curve.mint,
+          wsolMint: NATIVE_MINT,
+          curveTokenAccount: curve.curveTokenAccount,
+          ammConfig: accounts.ammConfig,
+        })
+        .view();
+
+      await program.methods
+        .migrate()
+        .accountsStrict(accounts)
+        .signers([migrator, positionNftMint])
+        .rpc();
+
+      await reconfigure({ migrationPriceToleranceBps: 500 });
+
+      const bondingCurve = await program.account.bondingCurve.fetch(curve.bondingCurve);
+      expect(bondingCurve.isMigrated).to.be.true;
+      expect(bondingCurve.pool.toBase58()).to.equal(poolState.toBase58());
+      expect(bondingCurve.lpMint.toBase58()).to.equal(positionNftMint.publicKey.toBase58());
+
+      // The whole SOL reserve is deposited, no migration fee is configured
+      const tokenIsMint0 = mint0.equals(curve.mint);
+      const [amount0, amount1] = tokenIsMint0
+        ? [tokenBalance, realSolReserve]
+        : [realSolReserve, tokenBalance];
+      const vault0 = await provider.connection.getTokenAccountBalance(tokenVault0);
+      const vault1 = await provider.connection.getTokenAccountBalance(tokenVault1);
+      expect(vault0.value.amount).to.equal(amount0.toString());
+      expect(vault1.value.amount).to.equal(amount1.toString());
+
+      // Q64.64 sqrt of amount_1 / amount_0 rounded down, inverted when the token comes first
+      const { sqrtPriceX64 } = await clmmStub.account.poolState.fetch(poolState);
+      const ratio = amount1.shln(128).div(amount0);
+      expect(sqrtPriceX64.sqr().lte(ratio)).to.be.true;
+      expect(sqrtPriceX64.addn(1).sqr().gt(ratio)).to.be.true;
+
+      // The preview matches what migrate created and moved
+      expect(preview.poolState.toBase58()).to.equal(poolState.toBase58());
+      expect(preview.tokenVault0.toBase58()).to.equal(tokenVault0.toBase58());
+      expect(preview.tokenVault1.toBase58()).to.equal(tokenVault1.toBase58());
+      expect(preview.plan.tokenIsMint0).to.equal(tokenIsMint0);
+      expect(preview.plan.amount0.toString()).to.equal(vault0.value.amount);
+      expect(preview.plan.amount1.toString()).to.equal(vault1.value.amount);
+      expect(preview.plan.sqrtPriceX64.toString()).to.equal(sqrtPriceX64.toString());
+
+      // Unused position rent went back to the migrator
+      expect(await provider.connection.getBalance(curve.positionAuthority)).to.equal(0);
+
+      return position;
+    };
+
+    it("Can migrate a CLMM curve into Raydium CLMM", async () => {
+      expect(sortedMints(clmmCurve.mint)[0].equals(NATIVE_MINT)).to.be.true;
+      clmmPosition = await migrateClmm(clmmCurve);
+    });
+
+    it("Can migrate a CLMM curve whose mint sorts before WSOL", async () => {
+      expect(sortedMints(tokenFirstCurve.mint)[0].equals(tokenFirstCurve.mint)).to.be.true;
+      await migrateClmm(tokenFirstCurve);
+    });
+
+    it("Should fail previewing a curve that does not migrate to CLMM", async () => {
+      try {
+        await program.methods
+          .previewMigration()
+          .accountsStrict({
+            config: configPda,
+            bondingCurve: cpmmCurve.bondingCurve,
+            tokenMint: cpmmCurve.mint,
+            wsolMint: NATIVE_MINT,
+            curveTokenAccount: cpmmCurve.curveTokenAccount,
+            ammConfig: pda([Buffer.from("amm_config"), u16(0)], clmmStub.programId),
+          })
+          .view();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.error?.errorCode?.code).to.equal("PreviewNotSupported");
+      }
+    });
+
+    it("Splits collected CLMM fees between the creator and the protocol", async () => {
+      // Fees accrue to the position as if traders had swapped through the pool, WSOL is mint 0
+      const solFees = 1_000_001;
+      const tokenFees = 1_000;
+      const payerWsol = await createWrappedNativeAccount(
+        provider.connection,
+        migrator,
+        migrator.publicKey,
+        solFees
+      );
+      await clmmStub.methods
+        .accrueFees(new anchor.BN(solFees), new anchor.BN(tokenFees))
+        .accountsStrict({
+          payer: migrator.publicKey,
+          payerToken0: payerWsol,
+          payerToken1: getAssociatedTokenAddressSync(clmmCurve.mint, migrator.publicKey),
+          poolState: clmmPosition.poolState,
+          personalPosition: clmmPosition.personalPosition,
+          tokenVault0: clmmPosition.tokenVault0,
+          tokenVault1: clmmPosition.tokenVault1,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([migrator])
+        .rpc();
+
+      // A separate protocol wallet so the two shares land in different accounts
+      const protocol = Keypair.generate();
+      await reconfigure({ feeRecipient: protocol.publicKey });
+
+      const accounts = {
+        creatorWsolAccount: getAssociatedTokenAddressSync(NATIVE_MINT, creator.publicKey),
+        creatorTokenAccount: getAssociatedTokenAddressSync(clmmCurve.mint, creator.publicKey),
+        feeRecipientWsolAccount: getAssociatedTokenAddressSync(NATIVE_MINT, protocol.publicKey),
+        feeRecipientTokenAccount: getAssociatedTokenAddressSync(clmmCurve.mint, protocol.publicKey),
+      };
+      const tokenAmount = async (account: PublicKey) => {
+        const balance = await provider.connection.getTokenAccountBalance(account).catch(() => null);
+        return new anchor.BN(balance?.value.amount ?? 0);
+      };
+      const creatorWsolBefore = await tokenAmount(accounts.creatorWsolAccount);
+
+      await program.methods
+        .collectPoolFees()
+        .accountsStrict({
+          ...accounts,
+          cranker: migrator.publicKey,
+          config: configPda,
+          bondingCurve: clmmCurve.bondingCurve,
+          tokenMint: clmmCurve.mint,
+          wsolMint: NATIVE_MINT,
+          positionAuthority: clmmCurve.positionAuthority,
+          positionNftMint: clmmPosition.positionNftMint,
+          positionNftAccount: clmmPosition.positionNftAccount,
+          positionWsolAccount: getAssociatedTokenAddressSync(
+            NATIVE_MINT,
+            clmmCurve.positionAuthority,
+            true
+          ),
+          positionTokenAccount: getAssociatedTokenAddressSync(
+            clmmCurve.mint,
+            clmmCurve.positionAuthority,
+            true
+          ),
+          poolState: clmmPosition.poolState,
+          protocolPosition: clmmPosition.protocolPosition,
+          personalPosition: clmmPosition.personalPosition,
+          tokenVault0: clmmPosition.tokenVault0,
+          tokenVault1: clmmPosition.tokenVault1,
+          tickArrayLower: clmmPosition.tickArrayLower,
+          tickArrayUpper: clmmPosition.tickArrayUpper,
+          creator: creator.publicKey,
+          feeRecipient: protocol.publicKey,
+          memoProgram: MEMO_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          tokenProgram2022: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          raydiumProgram: clmmStub.programId,
+        })
+        .signers([migrator])
+        .rpc();
+
+      await reconfigure({ feeRecipient: creator.publicKey });
+
+      // creatorLpFeeBps is 5000, the creator's half rounds down and the protocol keeps the odd unit
+      expect(
+        (await tokenAmount(accounts.creatorWsolAccount)).sub(creatorWsolBefore).toNumber()
+      ).to.equal(500_000);
+      expect((await tokenAmount(accounts.feeRecipientWsolAccount)).toNumber()).to.equal(500_001);
+      expect((await tokenAmount(accounts.creatorTokenAccount)).toNumber()).to.equal(500);
+      expect((await tokenAmount(accounts.feeRecipientTokenAccount)).toNumber()).to.equal(500);
+
+      // Nothing is left behind with the position
+      const position = await clmmStub.account.personalPositionState.fetch(
+        clmmPosition.personalPosition
+      );
+      expect(position.tokenFeesOwed0.toNumber()).to.equal(0);
+      expect(position.tokenFeesOwed1.toNumber()).to.equal(0);
+    });
+
+    it("Splits the protocol's share of CLMM fees by the fee split weights", async () => {
+      const solFees = 1_000_000;
+      const tokenFees = 1_000;
+      const payerWsol = await createWrappedNativeAccount(
+        provider.connection,
+        migrator,
+        migrator.publicKey,
+        solFees,
+        Keypair.generate()
+      );
+      await clmmStub.methods
+        .accrueFees(new anchor.BN(solFees), new anchor.BN(tokenFees))
+        .accountsStrict({
+          payer: migrator.publicKey,
+          payerToken0: payerWsol,
+          payerToken1: getAssociatedTokenAddressSync(clmmCurve.mint, migrator.publicKey),
+          poolState: clmmPosition.poolState,
+          personalPosition: clmmPosition.personalPosition,
+          tokenVault0: clmmPosition.tokenVault0,
+          tokenVault1: clmmPosition.tokenVault1,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([migrator])
+        .rpc();
+
+      // Split wallets pass their WSOL and token accounts in pairs, in the split's order
+      const treasury = Keypair.generate();
+      const buyback = Keypair.generate();
+      const splitTokenAccounts = [];
+      for (const wallet of [treasury.publicKey, buyback.publicKey]) {
+        for (const mint of [NATIVE_MINT, clmmCurve.mint]) {
+          splitTokenAccounts.push(
+            await createAssociatedTokenAccount(provider.connection, migrator, mint, wallet)
+          );
+        }
+      }
+      const protocol = Keypair.generate();
+      await reconfigure({
+        feeRecipient: protocol.publicKey,
+        feeSplit: [
+          { wallet: treasury.publicKey, weightBps: 7000 },
+          { wallet: buyback.publicKey, weightBps: 3000 },
+        ],
+      });
+
+      await program.methods
+        .collectPoolFees()
+        .accountsStrict({
+          cranker: migrator.publicKey,
+          config: configPda,
+          bondingCurve: clmmCurve.bondingCurve,
+          tokenMint: clmmCurve.mint,
+          wsolMint: NATIVE_MINT,
+          positionAuthority: clmmCurve.positionAuthority,
+          positionNftMint: clmmPosition.positionNftMint,
+          positionNftAccount: clmmPosition.positionNftAccount,
+          positionWsolAccount: getAssociatedTokenAddressSync(
+            NATIVE_MINT,
+            clmmCurve.positionAuthority,
+            true
+          ),
+          positionTokenAccount: getAssociatedTokenAddressSync(
+            clmmCurve.mint,
+            clmmCurve.positionAuthority,
+            true
+          ),
+          poolState: clmmPosition.poolState,
+          protocolPosition: clmmPosition.protocolPosition,
+          personalPosition: clmmPosition.personalPosition,
+          tokenVault0: clmmPosition.tokenVault0,
+          tokenVault1: clmmPosition.tokenVault1,
+          tickArrayLower: clmmPosition.tickArrayLower,
+          tickArrayUpper: clmmPosition.tickArrayUpper,
+          creator: creator.publicKey,
+          creatorWsolAccount: getAssociatedTokenAddressSync(NATIVE_MINT, creator.publicKey),
+          creatorTokenAccount: getAssociatedTokenAddressSync(clmmCurve.mint, creator.publicKey),
+          feeRecipient: protocol.publicKey,
+          feeRecipientWsolAccount: getAssociatedTokenAddressSync(NATIVE_MINT, protocol.publicKey),
+          feeRecipientTokenAccount: getAssociatedTokenAddressSync(clmmCurve.mint, protocol.publicKey),
+          memoProgram: MEMO_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          tokenProgram2022: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          raydiumProgram: clmmStub.programId,
+        })
+        .remainingAccounts(
+          splitTokenAccounts.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+        )
+        .signers([migrator])
+        .rpc();
+
+      await reconfigure({ feeRecipient: creator.publicKey, feeSplit: [] });
+
+      // The protocol keeps half, 500,000 lamports and 500 tokens, split 70/30
+      const amounts = await Promise.all(
+        splitTokenAccounts.map(async (account) =>
+          Number((await provider.connection.getTokenAccountBalance(account)).value.amount)
+        )
+      );
+      expect(amounts).to.deep.equal([350_000, 350, 150_000, 150]);
+
+      // Nothing goes to the fee recipient once a split is set
+      const protocolWsol = await provider.connection.getTokenAccountBalance(
+        getAssociatedTokenAddressSync(NATIVE_MINT, protocol.publicKey)
+      );
+      expect(protocolWsol.value.amount).to.equal("0");
+    });
+
+    it("Can migrate a CPMM curve into Raydium CPMM", async () => {
+      const ammConfig = pda([Buffer.from("amm_config"), u16(0)], cpmmStub.programId);
+      const [mint0, mint1] = sortedMints(cpmmCurve.mint);
+      const poolState = pda(
+        [Buffer.from("pool"), ammConfig.toBuffer(), mint0.toBuffer(), mint1.toBuffer()],
+        cpmmStub.programId
+      );
+      const lpMint = pda([Buffer.from("pool_lp_mint"), poolState.toBuffer()], cpmmStub.programId);
+      const positionLpAccount = getAssociatedTokenAddressSync(
+        lpMint,
+        cpmmCurve.positionAuthority,
+        true
+      );
+      const feeBefore = await provider.connection.getBalance(cpmmPoolFeeAccount);
+
+      await program.methods
+        .migrateCpmm()
+        .accountsStrict({
+          ...positionAccounts(cpmmCurve),
+          positionLpAccount,
+          ammConfig,
+          raydiumAuthority: pda(
+            [Buffer.from("vault_and_lp_mint_auth_seed")],
+            cpmmStub.programId
+          ),
+          poolState,
+          lpMint,
+          tokenVault0: pda(
+            [Buffer.from("pool_vault"), poolState.toBuffer(), mint0.toBuffer()],
+            cpmmStub.programId
+          ),
+          tokenVault1: pda(
+            [Buffer.from("pool_vault"), poolState.toBuffer(), mint1.toBuffer()],
+            cpmmStub.programId
+          ),
+          createPoolFee: cpmmPoolFeeAccount,
+          observationState: pda(
+            [Buffer.from("observation"), poolState.toBuffer()],
+            cpmmStub.programId
+          ),
+          raydiumProgram: cpmmStub.programId,
+        })
+        .signers([migrator])
+        .rpc();
+
+      const bondingCurve = await program.account.bondingCurve.fetch(cpmmCurve.bondingCurve);
+      expect(bondingCurve.isMigrated).to.be.true;
+      expect(bondingCurve.pool.toBase58()).to.equal(poolState.toBase58());
+      expect(bondingCurve.lpMint.toBase58()).to.equal(lpMint.toBase58());
+
+      const lp = await provider.connection.getTokenAccountBalance(positionLpAccount);
+      expect(new anchor.BN(lp.value.amount).gtn(0)).to.be.true;
+
+      // Raydium's creation fee is fronted by the migrator, the rest of the budget comes back
+      const feeAfter = await provider.connection.getBalance(cpmmPoolFeeAccount);
+      expect(feeAfter - feeBefore).to.equal(CPMM_CREATE_POOL_FEE);
+      expect(await provider.connection.getBalance(cpmmCurve.positionAuthority)).to.equal(0);
+    });
+
+    it("Can migrate an AMM v4 curve into Raydium AMM v4", async () => {
+      const { realSolReserve } = await program.account.bondingCurve.fetch(
+        ammV4Curve.bondingCurve
+      );
+      const market = Keypair.generate().publicKey;
+      const associated = (seed: string) =>
+        pda([ammV4Stub.programId.toBuffer(), market.toBuffer(), Buffer.from(seed)], ammV4Stub.programId);
+      const lpMint = associated("lp_mint_associated_seed");
+      const coinVault = associated("coin_vault_associated_seed");
+      const pcVault = associated("pc_vault_associated_seed");
+      const positionLpAccount = getAssociatedTokenAddressSync(
+        lpMint,
+        ammV4Curve.positionAuthority,
+        true
+      );
+      const feeReceiver = Keypair.generate().publicKey;
+
+      await program.methods
+        .migrateAmmV4()
+        .accountsStrict({
+          ...positionAccounts(ammV4Curve),
+          positionLpAccount,
+          ammConfig: pda([Buffer.from("amm_config_account_seed")], ammV4Stub.programId),
+          raydiumAuthority: pda([Buffer.from("amm authority")], ammV4Stub.programId),
+          poolState: associated("amm_associated_seed"),
+          openOrders: associated("open_order_associated_seed"),
+          lpMint,
+          coinVault,
+          pcVault,
+          targetOrders: associated("target_associated_seed"),
+          createPoolFee: feeReceiver,
+          market,
+          marketProgram: OPENBOOK_PROGRAM_ID,
+          raydiumProgram: ammV4Stub.programId,
+        })
+        .signers([migrator])
+        .rpc();
+
+      const bondingCurve = await program.account.bondingCurve.fetch(ammV4Curve.bondingCurve);
+      expect(bondingCurve.isMigrated).to.be.true;
+      expect(bondingCurve.lpMint.toBase58()).to.equal(lpMint.toBase58());
+
+      // Token on the coin side, SOL on the pc side
+      const coin = await provider.connection.getTokenAccountBalance(coinVault);
+      expect(new anchor.BN(coin.value.amount).gtn(0)).to.be.true;
+      const pc = await provider.connection.getTokenAccountBalance(pcVault);
+      expect(pc.value.amount).to.equal(realSolReserve.toString());
+
+      const lp = await provider.connection.getTokenAccountBalance(positionLpAccount);
+      expect(new anchor.BN(lp.value.amount).gtn(0)).to.be.true;
+      expect(await provider.connection.getBalance(feeReceiver)).to.equal(AMM_V4_CREATE_POOL_FEE);
+      expect(await provider.connection.getBalance(ammV4Curve.positionAuthority)).to.equal(0);
+    });
+
+    it("Can migrate into the built-in pool and trade against it", async () => {
+      const accounts = builtInPoolAccounts(ammCurve);
+      await program.methods
+        .migrateAmm()
+        .accountsPartial(accounts)
+        .signers([migrator])
+        .rpc();
+
+      const bondingCurve = await program.account.bondingCurve.fetch(ammCurve.bondingCurve);
+      expect(bondingCurve.isMigrated).to.be.true;
+
+      const swapAccounts = {
+        user: migrator.publicKey,
+        ammPool: accounts.ammPool,
+        tokenMint: ammCurve.mint,
+        ammSolVault: accounts.ammSolVault,
+        ammTokenAccount: accounts.ammTokenAccount,
+        userTokenAccount: getAssociatedTokenAddressSync(ammCurve.mint, migrator.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      };
+      const tokensBefore = new anchor.BN(
+        (await provider.connection.getTokenAccountBalance(swapAccounts.userTokenAccount)).value.amount
+      );
+
+      // Buy
+      await program.methods
+        .ammSwap(new anchor.BN(10_000_000), 0, new anchor.BN(1))
+        .accountsStrict(swapAccounts)
+        .signers([migrator])
+        .rpc();
+
+      const tokensAfter = new anchor.BN(
+        (await provider.connection.getTokenAccountBalance(swapAccounts.userTokenAccount)).value.amount
+      );
+      const bought = tokensAfter.sub(tokensBefore);
+      expect(bought.gtn(0)).to.be.true;
+
+      // Sell it all back
+      await program.methods
+        .ammSwap(bought, 1, new anchor.BN(1))
+        .accountsStrict(swapAccounts)
+        .signers([migrator])
+        .rpc();
+
+      // The SOL vault always holds the SOL reserve on top of its rent-exempt minimum
+      const pool = await program.account.ammPool.fetch(accounts.ammPool);
+      const rent = await provider.connection.getMinimumBalanceForRentExemption(0);
+      expect(await provider.connection.getBalance(accounts.ammSolVault)).to.equal(
+        pool.solReserve.toNumber() + rent
+      );
+    });
+
+    it("Lets the creator claim fees after the curve is finalized", async () => {
+      const { creatorFees } = await program.account.bondingCurve.fetch(ammCurve.bondingCurve);
+      expect(creatorFees.gtn(0)).to.be.true;
+
+      await program.methods
+        .finalize()
+        .accountsStrict({
+          globalConfig: configPda,
+          feeStats: feeStatsPda,
+          feeVault: feeVaultPda,
+          bondingCurve: ammCurve.bondingCurve,
+          solVault: ammCurve.solVault,
+          tokenMint: ammCurve.mint,
+          curveTokenAccount: ammCurve.curveTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      // The creator is read off the tombstone once the curve is gone
+      const creatorBefore = await provider.connection.getBalance(creator.publicKey);
+      await program.methods
+        .claimCreatorFees()
+        .accountsStrict({
+          creator: creator.publicKey,
+          bondingCurve: ammCurve.bondingCurve,
+          creatorVault: ammCurve.creatorVault,
+          tokenMint: ammCurve.mint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+      const creatorAfter = await provider.connection.getBalance(creator.publicKey);
+      expect(creatorAfter - creatorBefore).to.equal(creatorFees.toNumber());
+
+      const rent = await provider.connection.getMinimumBalanceForRentExemption(0);
+      expect(await provider.connection.getBalance(ammCurve.creatorVault)).to.equal(rent);
+    });
+
+    it("Should fail migrate by anyone but the configured migrator", async () => {
+      await reconfigure({ migrator: creator.publicKey });
+      try {
+        const curve = await completedCurve({ constantProduct: {} });
+        await program.methods
+          .migrateAmm()
+          .accountsPartial(builtInPoolAccounts(curve))
+          .signers([migrator])
+          .rpc();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.error?.errorCode?.code).to.equal("UnauthorizedAddress");
+      } finally {
+        await reconfigure({ migrator: PublicKey.default });
+      }
+    });
+
+    it("Pays the migration bounty out of the migration fee", async () => {
+      const bounty = new anchor.BN(1_000_000);
+      await reconfigure({ migrationFeePercentage: 2, migrationBounty: bounty });
+      try {
+        const curve = await completedCurve({ constantProduct: {} });
+        const accounts = builtInPoolAccounts(curve);
+        const { realSolReserve } = await program.account.bondingCurve.fetch(curve.bondingCurve);
+        const migrationFee = realSolReserve.muln(2).divn(100);
+        expect(migrationFee.gt(bounty)).to.be.true;
+
+        const migratorBefore = await provider.connection.getBalance(migrator.publicKey);
+        const feeVaultBefore = await provider.connection.getBalance(feeVaultPda);
+        const statsBefore = await program.account.feeStats.fetch(feeStatsPda);
+
+        await program.methods
+          .migrateAmm()
+          .accountsPartial(accounts)
+          .signers([migrator])
+          .rpc();
+
+        // The migrator pays the pool's accounts and rent, and earns the bounty back
+        const rent = await provider.connection.getMinimumBalanceForRentExemption(0);
+        const poolRent =
+          (await provider.connection.getBalance(accounts.ammPool)) +
+          (await provider.connection.getBalance(accounts.ammTokenAccount)) +
+          rent;
+        const migratorAfter = await provider.connection.getBalance(migrator.publicKey);
+        expect(migratorAfter - migratorBefore).to.equal(bounty.toNumber() - poolRent);
+
+        // Only the rest of the fee accrues to the protocol
+        const protocolFee = migrationFee.sub(bounty);
+        const feeVaultAfter = await provider.connection.getBalance(feeVaultPda);
+        expect(feeVaultAfter - feeVaultBefore).to.equal(protocolFee.toNumber());
+        const statsAfter = await program.account.feeStats.fetch(feeStatsPda);
+        expect(statsAfter.migrationFees.sub(statsBefore.migrationFees).toString()).to.equal(
+          protocolFee.toString()
+        );
+
+        const pool = await program.account.ammPool.fetch(accounts.ammPool);
+        expect(pool.solReserve.toString()).to.equal(realSolReserve.sub(migrationFee).toString());
+      } finally {
+        await reconfigure({ migrationFeePercentage: 0, migrationBounty: new anchor.BN(0) });
+      }
+    });
+
+    it("Burns token dust on finalize and lets anyone unwind orders left on the tombstone", async () => {
+      await reconfigure({
+        migrationTarget: { constantProduct: {} },
+        curveLimit: new anchor.BN(1.1 * LAMPORTS_PER_SOL),
+      });
+      const curve = await launchCurve();
+
+      // A resting buy order that never fills before the curve completes
+      const orderId = new anchor.BN(1);
+      const limitOrder = pda([
+        Buffer.from("limit_order"),
+        curve.mint.toBuffer(),
+        user.publicKey.toBuffer(),
+        orderId.toArrayLike(Buffer, "le", 8),
+      ]);
+      const orderTokenAccount = getAssociatedTokenAddressSync(curve.mint, limitOrder, true);
+      const ownerTokenAccount = getAssociatedTokenAddressSync(curve.mint, user.publicKey);
+      await program.methods
+        .placeOrder(
+          orderId,
+          0,
+          new anchor.BN(10000),
+          new anchor.BN(1),
+          new anchor.BN(1),
+          new anchor.BN(Math.floor(Date.now() / 1000) + 3600)
+        )
+        .accountsStrict({
+          owner: user.publicKey,
+          globalConfig: configPda,
+          bondingCurve: curve.bondingCurve,
+          tokenMint: curve.mint,
+          limitOrder,
+          orderTokenAccount,
+          ownerTokenAccount,
+          referrer: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+
+      await program.methods
+        .swap(new anchor.BN(0.2 * LAMPORTS_PER_SOL), 0, new anchor.BN(1), null)
+        .accountsStrict(swapAccounts(migrator.publicKey, curve.mint))
+        .signers([migrator])
+        .rpc();
+      await reconfigure({ migrationTarget: { raydiumClmm: {} }, curveLimit });
+
+      await program.methods
+        .migrateAmm()
+        .accountsPartial(builtInPoolAccounts(curve))
+        .signers([migrator])
+        .rpc();
+
+      // A token sent to the emptied curve account must not hold finalize up
+      await transfer(
+        provider.connection,
+        migrator,
+        getAssociatedTokenAddressSync(curve.mint, migrator.publicKey),
+        curve.curveTokenAccount,
+        migrator,
+        1
+      );
+      const vaultBefore = await provider.connection.getBalance(feeVaultPda);
+      const statsBefore = await program.account.feeStats.fetch(feeStatsPda);
+      await program.methods
+        .finalize()
+        .accountsStrict({
+          globalConfig: configPda,
+          feeStats: feeStatsPda,
+          feeVault: feeVaultPda,
+          bondingCurve: curve.bondingCurve,
+          solVault: curve.solVault,
+          tokenMint: curve.mint,
+          curveTokenAccount: curve.curveTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      expect(await provider.connection.getAccountInfo(curve.curveTokenAccount)).to.be.null;
+
+      // The reclaimed rent and leftovers accrue into the fee vault, not to the fee recipient
+      const statsAfter = await program.account.feeStats.fetch(feeStatsPda);
+      const swept = statsAfter.finalizeSweeps.sub(statsBefore.finalizeSweeps).toNumber();
+      expect(swept).to.be.greaterThan(0);
+      expect(await provider.connection.getBalance(feeVaultPda)).to.equal(vaultBefore + swept);
+
+      // The curve is a tombstone now, so anyone can clean up the order for its crank fee
+      const { crankFee } = await program.account.limitOrder.fetch(limitOrder);
+      const cancellerBefore = await provider.connection.getBalance(creator.publicKey);
+      await program.methods
+        .cancelOrder()
+        .accountsStrict({
+          canceller: creator.publicKey,
+          bondingCurve: curve.bondingCurve,
+          tokenMint: curve.mint,
+          limitOrder,
+          orderTokenAccount,
+          owner: user.publicKey,
+          ownerTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([creator])
+        .rpc();
+      const cancellerAfter = await provider.connection.getBalance(creator.publicKey);
+
+      expect(cancellerAfter - cancellerBefore).to.equal(crankFee.toNumber());
+      expect(await provider.connection.getAccountInfo(limitOrder)).to.be.null;
+      expect(await provider.connection.getAccountInfo(orderTokenAccount)).to.be.null;
+    });
+
+    it("Should fail finalize before migration", async () => {
+      try {
+        await program.methods
+          .finalize()
+          .accounts({
+            globalConfig: configPda,
+            feeStats: feeStatsPda,
+            feeVault: feeVaultPda,
+            bondingCurve: bondingCurvePda,
+            tokenMint: tokenMint.publicKey,
+            curveTokenAccount: curveTokenAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error).to.exist;
+      }
+    });
+  });
+
+  describe("Refund tests", () => {
+    it("Should fail refund on a curve without a graduation deadline", async () => {
+      const bondingCurve = await program.account.bondingCurve.fetch(
+        bondingCurvePda
+      );
+      expect(bondingCurve.graduationDeadline.toNumber()).to.equal(0);
+      expect(bondingCurve.isRefunding).to.be.false;
+
+      try {
+        await program.methods
+          .refund(new anchor.BN(1000))
+          .accounts({
+            holder: user.publicKey,
+            bondingCurve: bondingCurvePda,
+            tokenMint: tokenMint.publicKey,
+            curveTokenAccount: curveTokenAccount,
+            holderTokenAccount: userTokenAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user])
+          .rpc();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error).to.exist;
+      }
+    });
+
+    describe("After the graduation deadline", () => {
+      const holders = [Keypair.generate(), Keypair.generate()];
+      let curve: CurveAccounts;
+
+      const refundAccounts = (holder: Keypair) => ({
+        holder: holder.publicKey,
+        bondingCurve: curve.bondingCurve,
+        solVault: curve.solVault,
+        tokenMint: curve.mint,
+        curveTokenAccount: curve.curveTokenAccount,
+        holderTokenAccount: getAssociatedTokenAddressSync(curve.mint, holder.publicKey),
+        refundClaim: pda([
+          Buffer.from("refund_claim"),
+          curve.mint.toBuffer(),
+          holder.publicKey.toBuffer(),
+        ]),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      });
+
+      const tokenBalance = async (holder: Keypair) =>
+        new anchor.BN(
+          (
+            await provider.connection.getTokenAccountBalance(
+              getAssociatedTokenAddressSync(curve.mint, holder.publicKey)
+            )
+          ).value.amount
+        );
+
+      before(async () => {
+        for (const holder of holders) {
+          await provider.connection.requestAirdrop(holder.publicKey, LAMPORTS_PER_SOL);
+        }
+        await new Promise((resolve) => setTimeout(resolve, 1000));
+
+        // Short window, so the curve is still trading while the holders buy in
+        await reconfigure({ graduationWindow: new anchor.BN(3) });
+        curve = await launchCurve();
+        await reconfigure({ graduationWindow: new anchor.BN(0) });
+
+        await program.methods
+          .swap(new anchor.BN(0.01 * LAMPORTS_PER_SOL), 0, new anchor.BN(1), null)
+          .accountsStrict(swapAccounts(holders[0].publicKey, curve.mint))
+          .signers([holders[0]])
+          .rpc();
+        await program.methods
+          .swap(new anchor.BN(0.03 * LAMPORTS_PER_SOL), 0, new anchor.BN(1), null)
+          .accountsStrict(swapAccounts(holders[1].publicKey, curve.mint))
+          .signers([holders[1]])
+          .rpc();
+
+        await new Promise((resolve) => setTimeout(resolve, 4000));
+      });
+
+      it("Should fail buying or selling once the deadline passed", async () => {
+        for (const direction of [0, 1]) {
+          try {
+            await program.methods
+              .swap(new anchor.BN(1_000_000), direction, new anchor.BN(1), null)
+              .accountsStrict(swapAccounts(holders[0].publicKey, curve.mint))
+              .signers([holders[0]])
+              .rpc();
+
+            assert.fail("Should have thrown error");
+          } catch (error) {
+            expect(error.error?.errorCode?.code).to.equal("CurveRefunding");
+          }
+        }
+      });
+
+      it("Refunds each holder their pro-rata share of the SOL reserve", async () => {
+        const balances = [await tokenBalance(holders[0]), await tokenBalance(holders[1])];
+        const { realSolReserve } = await program.account.bondingCurve.fetch(curve.bondingCurve);
+
+        for (const [i, holder] of holders.entries()) {
+          const before = await provider.connection.getBalance(holder.publicKey);
+          await program.methods
+            .refund(balances[i])
+            .accountsStrict(refundAccounts(holder))
+            .signers([holder])
+            .rpc();
+          const after = await provider.connection.getBalance(holder.publicKey);
+
+          // Opening refunds froze the reserve and the supply held outside the curve
+          const state = await program.account.bondingCurve.fetch(curve.bondingCurve);
+          expect(state.isRefunding).to.be.true;
+          expect(state.refundSolReserve.toString()).to.equal(realSolReserve.toString());
+          expect(state.refundTokenSupply.toString()).to.equal(
+            balances[0].add(balances[1]).toString()
+          );
+
+          const share = balances[i].mul(realSolReserve).div(state.refundTokenSupply);
+          const claim = await program.account.refundClaim.fetch(refundAccounts(holder).refundClaim);
+          expect(claim.solClaimed.toString()).to.equal(share.toString());
+          expect(claim.tokensBurned.toString()).to.equal(balances[i].toString());
+
+          // The holder pays for their claim record out of the refund
+          const claimRent = await provider.connection.getBalance(refundAccounts(holder).refundClaim);
+          expect(after - before).to.equal(share.toNumber() - claimRent);
+          expect((await tokenBalance(holder)).toNumber()).to.equal(0);
+        }
+      });
+
+      it("Should fail a second redemption", async () => {
+        try {
+          await program.methods
+            .refund(new anchor.BN(1))
+            .accountsStrict(refundAccounts(holders[0]))
+            .signers([holders[0]])
+            .rpc();
+
+          assert.fail("Should have thrown error");
+        } catch (error) {
+          expect(error.error?.errorCode?.code).to.equal("InsufficientTokenBalance");
+        }
+      });
+    });
+  });
+
+  describe("Audit tests", () => {
+    const audit = (curve: CurveAccounts) =>
+      program.methods
+        .auditCurve()
+        .accounts({
+          auditor: user.publicKey,
+          globalConfig: configPda,
+          bondingCurve: curve.bondingCurve,
+          tokenMint: curve.mint,
+          curveTokenAccount: curve.curveTokenAccount,
+          solVault: curve.solVault,
+        })
+        .signers([user])
+        .rpc();
+
+    it("Can audit a curve without pausing it", async () => {
+      // The shared vault holds a donation on top of its reserve, a surplus never pauses
+      await reconfigure({ auditPauseThreshold: new anchor.BN(1) });
+      try {
+        await audit(curveAccounts(tokenMint.publicKey));
+      } finally {
+        await reconfigure({ auditPauseThreshold: new anchor.BN(0) });
+      }
+
+      const bondingCurve = await program.account.bondingCurve.fetch(
+        bondingCurvePda
+      );
+      expect(bondingCurve.isPaused).to.be.false;
+    });
+
+    it("Pauses a curve short of its token reserve and blocks buys on it", async () => {
+      const { totalTokenSupply, initialRealTokenReserve } = await program.account.config.fetch(
+        configPda
+      );
+
+      // Launch books more tokens for sale than it mints, the curve is short from the start
+      await reconfigure({ initialRealTokenReserve: totalTokenSupply.addn(1000) });
+      let curve: CurveAccounts;
+      try {
+        curve = await launchCurve();
+      } finally {
+        await reconfigure({ initialRealTokenReserve });
+      }
+
+      await reconfigure({ auditPauseThreshold: new anchor.BN(1000) });
+      try {
+        await audit(curve);
+      } finally {
+        await reconfigure({ auditPauseThreshold: new anchor.BN(0) });
+      }
+
+      const bondingCurve = await program.account.bondingCurve.fetch(curve.bondingCurve);
+      expect(bondingCurve.isPaused).to.be.true;
+
+      try {
+        await program.methods
+          .swap(new anchor.BN(10000), 0, new anchor.BN(1), null)
+          .accountsStrict(swapAccounts(user.publicKey, curve.mint))
+          .signers([user])
+          .rpc();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.error?.errorCode?.code).to.equal("CurvePaused");
+      }
+    });
+
+    it("Should fail pausing a curve without the authority", async () => {
+      try {
+        await program.methods
+          .setCurvePause(true)
+          .accounts({
+            authority: user.publicKey,
+            globalConfig: configPda,
+            bondingCurve: bondingCurvePda,
+            tokenMint: tokenMint.publicKey,
+          })
+          .signers([user])
+          .rpc();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error).to.exist;
+      }
+    });
+  });
+
+  describe("Fee vault tests", () => {
+    it("Accrues trading fees into the fee vault", async () => {
+      const feeStats = await program.account.feeStats.fetch(feeStatsPda);
+      expect(feeStats.buyFees.toNumber()).to.be.greaterThan(0);
+
+      const vaultBalance = await provider.connection.getBalance(feeVaultPda);
+      const rent = await provider.connection.getMinimumBalanceForRentExemption(0);
+      const accrued = feeStats.buyFees
+        .add(feeStats.sellFees)
+        .add(feeStats.migrationFees)
+        .add(feeStats.launchFees)
+        .add(feeStats.finalizeSweeps)
+        .sub(feeStats.withdrawn)
+        .sub(feeStats.distributed);
+      expect(vaultBalance).to.equal(rent + accrued.toNumber());
+    });
+
+    it("Should fail configuring a fee split that does not sum to 10,000", async () => {
+      const current = await program.account.config.fetch(configPda);
+
+      try {
+        await program.methods
+          .configure({
+            ...current,
+            feeSplit: [
+              { wallet: creator.publicKey, weightBps: 5000 },
+              { wallet: user.publicKey, weightBps: 4000 },
+            ],
+          })
+          .accounts({
+            admin: creator.publicKey,
+            globalConfig: configPda,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.error?.errorCode?.code).to.equal("InvalidFeeSplit");
+      }
+    });
+
+    it("Should fail withdrawing fees without the fee manager", async () => {
+      try {
+        await program.methods
+          .withdrawFees(new anchor.BN(1))
+          .accounts({
+            feeManager: user.publicKey,
+            globalConfig: configPda,
+            feeRecipient: creator.publicKey,
+          })
+          .signers([user])
+          .rpc();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error).to.exist;
+      }
+    });
+
+    it("Can withdraw fees to the fee recipient", async () => {
+      const before = await program.account.feeStats.fetch(feeStatsPda);
+
+      await program.methods
+        .withdrawFees(new anchor.BN(1))
+        .accounts({
+          feeManager: creator.publicKey,
+          globalConfig: configPda,
+          feeRecipient: creator.publicKey,
+        })
+        .signers([creator])
+        .rpc();
+
+      const after = await program.account.feeStats.fetch(feeStatsPda);
+      expect(after.withdrawn.sub(before.withdrawn).toNumber()).to.equal(1);
+    });
+
+    it("Should fail withdrawing into the fee vault's rent", async () => {
+      const vaultBalance = await provider.connection.getBalance(feeVaultPda);
+      const rent = await provider.connection.getMinimumBalanceForRentExemption(0);
+
+      try {
+        await program.methods
+          .withdrawFees(new anchor.BN(vaultBalance - rent + 1))
+          .accounts({
+            feeManager: creator.publicKey,
+            globalConfig: configPda,
+            feeRecipient: creator.publicKey,
+          })
+          .signers([creator])
+          .rpc();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.error?.errorCode?.code).to.equal("VaultBelowRentExempt");
+      }
+    });
+  });
+
+  describe("Fee split tests", () => {
+    const treasury = Keypair.generate();
+    const buyback = Keypair.generate();
+    const insurance = Keypair.generate();
+    const feeSplit = [
+      { wallet: treasury.publicKey, weightBps: 6000 },
+      { wallet: buyback.publicKey, weightBps: 3000 },
+      { wallet: insurance.publicKey, weightBps: 1000 },
+    ];
+    const splitAccounts = feeSplit.map(({ wallet }) => ({
+      pubkey: wallet,
+      isWritable: true,
+      isSigner: false,
+    }));
+    const balances = () =>
+      Promise.all(feeSplit.map(({ wallet }) => provider.connection.getBalance(wallet)));
+
+    before(async () => {
+      // Funded up front so small cuts never land in an account below rent
+      const funding = new Transaction();
+      for (const { wallet } of feeSplit) {
+        funding.add(
+          SystemProgram.transfer({
+            fromPubkey: creator.publicKey,
+            toPubkey: wallet,
+            lamports: LAMPORTS_PER_SOL / 100,
+          })
+        );
+      }
+      await sendAndConfirmTransaction(provider.connection, funding, [creator]);
+
+      await reconfigure({ feeSplit });
+    });
+
+    after(async () => {
+      await reconfigure({ feeSplit: [], feeSplitPerTrade: false });
+    });
+
+    it("Splits a withdrawal between the fee split wallets by weight", async () => {
+      const before = await balances();
+
+      await program.methods
+        .withdrawFees(new anchor.BN(10_000))
+        .accounts({
+          feeManager: creator.publicKey,
+          globalConfig: configPda,
+          feeRecipient: creator.publicKey,
+        })
+        .remainingAccounts(splitAccounts)
+        .signers([creator])
+        .rpc();
+
+      const after = await balances();
+      expect(after.map((balance, i) => balance - before[i])).to.deep.equal([6000, 3000, 1000]);
+    });
+
+    it("Distributes only the trade's own protocol fee per trade", async () => {
+      await reconfigure({ feeSplitPerTrade: true });
+
+      const vaultBefore = await provider.connection.getBalance(feeVaultPda);
+      const statsBefore = await program.account.feeStats.fetch(feeStatsPda);
+      const before = await balances();
+
+      await program.methods
+        .swap(new anchor.BN(LAMPORTS_PER_SOL / 100), 0, new anchor.BN(1), null)
+        .accountsStrict(swapAccounts(user.publicKey))
+        .remainingAccounts(splitAccounts)
+        .signers([user])
+        .rpc();
+
+      // Fees accrued before the trade stay in the vault
+      expect(await provider.connection.getBalance(feeVaultPda)).to.equal(vaultBefore);
+
+      const statsAfter = await program.account.feeStats.fetch(feeStatsPda);
+      const protocolFee = statsAfter.buyFees.sub(statsBefore.buyFees).toNumber();
+      expect(protocolFee).to.be.greaterThan(0);
+
+      const after = await balances();
+      const cuts = after.map((balance, i) => balance - before[i]);
+      expect(cuts[0]).to.equal(Math.floor((protocolFee * 6000) / 10000));
+      expect(cuts[1]).to.equal(Math.floor((protocolFee * 3000) / 10000));
+      expect(cuts[0] + cuts[1] + cuts[2]).to.equal(protocolFee);
+
+      // Counted as distributed, withdrawals only count what the fee manager moved
+      expect(statsAfter.distributed.sub(statsBefore.distributed).toNumber()).to.equal(protocolFee);
+      expect(statsAfter.withdrawn.eq(statsBefore.withdrawn)).to.be.true;
+    });
+
+    it("Keeps the fee in the vault when a trade leaves out the split wallets", async () => {
+      const vaultBefore = await provider.connection.getBalance(feeVaultPda);
+      const statsBefore = await program.account.feeStats.fetch(feeStatsPda);
+
+      await program.methods
+        .swap(new anchor.BN(LAMPORTS_PER_SOL / 100), 0, new anchor.BN(1), null)
+        .accountsStrict(swapAccounts(user.publicKey))
+        .signers([user])
+        .rpc();
+
+      const statsAfter = await program.account.feeStats.fetch(feeStatsPda);
+      const protocolFee = statsAfter.buyFees.sub(statsBefore.buyFees).toNumber();
+      expect(await provider.connection.getBalance(feeVaultPda)).to.equal(vaultBefore + protocolFee);
+    });
+  });
+
+  describe("Creator fee tests", () => {
+    // Launched at the maximum creator fee the config allows
+    let curve: CurveAccounts;
+
+    const claimAccounts = (claimer: PublicKey) => ({
+      creator: claimer,
+      bondingCurve: curve.bondingCurve,
+      creatorVault: curve.creatorVault,
+      tokenMint: curve.mint,
+      systemProgram: SystemProgram.programId,
+    });
+
+    before(async () => {
+      curve = await launchCurve(100);
+    });
+
+    it("Records the creator fee chosen at launch", async () => {
+      const bondingCurve = await program.account.bondingCurve.fetch(curve.bondingCurve);
+      expect(bondingCurve.creatorFeeBps).to.equal(100);
+      expect(bondingCurve.creatorFees.toNumber()).to.equal(0);
+    });
+
+    it("Should fail claiming creator fees when none accrued", async () => {
+      try {
+        await program.methods
+          .claimCreatorFees()
+          .accountsStrict(claimAccounts(creator.publicKey))
+          .signers([creator])
+          .rpc();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.error?.errorCode?.code).to.equal("NothingToClaim");
+      }
+    });
+
+    it("Accrues the creator fee into the creator vault on a buy", async () => {
+      const amount = LAMPORTS_PER_SOL / 100;
+      const vaultBefore = await provider.connection.getBalance(curve.creatorVault);
+
+      await program.methods
+        .swap(new anchor.BN(amount), 0, new anchor.BN(1), null)
+        .accountsStrict(swapAccounts(user.publicKey, curve.mint))
+        .signers([user])
+        .rpc();
+
+      // 1% of the SOL paid in
+      const bondingCurve = await program.account.bondingCurve.fetch(curve.bondingCurve);
+      expect(bondingCurve.creatorFees.toNumber()).to.equal(amount / 100);
+      const vaultAfter = await provider.connection.getBalance(curve.creatorVault);
+      expect(vaultAfter - vaultBefore).to.equal(amount / 100);
+    });
+
+    it("Should fail claiming creator fees as someone other than the creator", async () => {
+      try {
+        await program.methods
+          .claimCreatorFees()
+          .accountsStrict(claimAccounts(user.publicKey))
+          .signers([user])
+          .rpc();
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.error?.errorCode?.code).to.equal("UnauthorizedAddress");
+      }
+    });
+
+    it("Can claim creator fees", async () => {
+      const { creatorFees } = await program.account.bondingCurve.fetch(curve.bondingCurve);
+      const creatorBefore = await provider.connection.getBalance(creator.publicKey);
+
+      await program.methods
+        .claimCreatorFees()
+        .accountsStrict(claimAccounts(creator.publicKey))
+        .signers([creator])
+        .rpc();
+
+      // Everything above the vault's rent goes to the creator
+      const creatorAfter = await provider.connection.getBalance(creator.publicKey);
+      expect(creatorAfter - creatorBefore).to.equal(creatorFees.toNumber());
+      const rent = await provider.connection.getMinimumBalanceForRentExemption(0);
+      expect(await provider.connection.getBalance(curve.creatorVault)).to.equal(rent);
+    });
+  });
+
+  describe("Referral tests", () => {
+    const referrerOwner = Keypair.generate();
+    let referrerPda: PublicKey;
+
+    before(async () => {
+      await provider.connection.requestAirdrop(
+        referrerOwner.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+
+      [referrerPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("referrer"), referrerOwner.publicKey.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("Should fail registering a share above the cap", async () => {
+      try {
+        await program.methods
+          .registerReferrer(5000)
+          .accounts({
+            owner: referrerOwner.publicKey,
+            globalConfig: configPda,
+          })
+          .signers([referrerOwner])
+          .rpc();
 
         assert.fail("Should have thrown error");
       } catch (error) {
         expect(error).to.exist;
       }
     });
+
+    it("Can register a referrer", async () => {
+      await program.methods
+        .registerReferrer(1000)
+        .accounts({
+          owner: referrerOwner.publicKey,
+          globalConfig: configPda,
+        })
+        .signers([referrerOwner])
+        .rpc();
+
+      const referrer = await program.account.referrer.fetch(referrerPda);
+      expect(referrer.shareBps).to.equal(1000);
+    });
+
+    it("Credits the referrer on a referred buy", async () => {
+      const userTokenAccount = await getAssociatedTokenAddress(
+        tokenMint.publicKey,
+        user.publicKey
+      );
+
+      await program.methods
+        .swap(new anchor.BN(100000), 0, new anchor.BN(1), null)
+        .accounts({
+          user: user.publicKey,
+          globalConfig: configPda,
+          creatorVault: creatorVaultPda,
+          feeStats: feeStatsPda,
+          feeVault: feeVaultPda,
+          bondingCurve: bondingCurvePda,
+          tokenMint: tokenMint.publicKey,
+          curveTokenAccount: curveTokenAccount,
+          userTokenAccount: userTokenAccount,
+          referrer: referrerPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+
+      const referrer = await program.account.referrer.fetch(referrerPda);
+      expect(referrer.referredTrades.toNumber()).to.equal(1);
+      expect(referrer.feesEarned.toNumber()).to.be.greaterThan(0);
+    });
+
+    it("Credits the referrer recorded on a filled order", async () => {
+      const orderId = new anchor.BN(7);
+      const limitOrder = pda([
+        Buffer.from("limit_order"),
+        tokenMint.publicKey.toBuffer(),
+        user.publicKey.toBuffer(),
+        orderId.toArrayLike(Buffer, "le", 8),
+      ]);
+      const orderTokenAccount = getAssociatedTokenAddressSync(tokenMint.publicKey, limitOrder, true);
+      const userTokenAccount = getAssociatedTokenAddressSync(tokenMint.publicKey, user.publicKey);
+      const expiresAt = new anchor.BN(Math.floor(Date.now() / 1000) + 3600);
+
+      // Limit far above the spot price, so the buy fills right away
+      await program.methods
+        .placeOrder(orderId, 0, new anchor.BN(100000), new anchor.BN("1000000000000000000"), new anchor.BN(1), expiresAt)
+        .accountsStrict({
+          owner: user.publicKey,
+          globalConfig: configPda,
+          bondingCurve: bondingCurvePda,
+          tokenMint: tokenMint.publicKey,
+          limitOrder,
+          orderTokenAccount,
+          ownerTokenAccount: userTokenAccount,
+          referrer: referrerPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+
+      const order = await program.account.limitOrder.fetch(limitOrder);
+      expect(order.referrer.toBase58()).to.equal(referrerPda.toBase58());
+
+      const fill = (referrer: PublicKey | null) =>
+        program.methods
+          .fillOrder()
+          .accountsStrict({
+            cranker: creator.publicKey,
+            globalConfig: configPda,
+            creatorVault: creatorVaultPda,
+            feeStats: feeStatsPda,
+            feeVault: feeVaultPda,
+            bondingCurve: bondingCurvePda,
+            solVault: solVaultPda,
+            tokenMint: tokenMint.publicKey,
+            curveTokenAccount: curveTokenAccount,
+            limitOrder,
+            orderTokenAccount,
+            owner: user.publicKey,
+            ownerTokenAccount: userTokenAccount,
+            tradeTracker: tradeTrackerPda(user.publicKey),
+            referrer,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+
+      // The cranker cannot drop the order's referrer
+      try {
+        await fill(null);
+
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        expect(error.error?.errorCode?.code).to.equal("InvalidReferrer");
+      }
+
+      const before = await program.account.referrer.fetch(referrerPda);
+      await fill(referrerPda);
+      const after = await program.account.referrer.fetch(referrerPda);
+      expect(after.referredTrades.toNumber()).to.equal(before.referredTrades.toNumber() + 1);
+      expect(after.feesEarned.gt(before.feesEarned)).to.be.true;
+    });
+
+    it("Can claim referral fees", async () => {
+      await program.methods
+        .claimReferralFees()
+        .accounts({
+          owner: referrerOwner.publicKey,
+        })
+        .signers([referrerOwner])
+        .rpc();
+
+      const referrer = await program.account.referrer.fetch(referrerPda);
+      expect(referrer.feesClaimed.toString()).to.equal(
+        referrer.feesEarned.toString()
+      );
+    });
   });
 
   describe("Edge cases", () => {
@@ -544,7 +3477,9 @@ describe("pump-fun", () => {
       const buyConfig = {
         user: testUser.publicKey,
         globalConfig: configPda,
-        feeRecipient: creator.publicKey,
+        creatorVault: creatorVaultPda,
+        feeStats: feeStatsPda,
+        feeVault: feeVaultPda,
         bondingCurve: bondingCurvePda,
         tokenMint: tokenMint.publicKey,
         curveTokenAccount: curveTokenAccount,
@@ -555,7 +3490,7 @@ describe("pump-fun", () => {
       };
 
       await program.methods
-        .swap(minBuyAmount, 0, new anchor.BN(1))
+        .swap(minBuyAmount, 0, new anchor.BN(1), null)
         .accounts(buyConfig)
         .signers([testUser])
         .rpc();
@@ -585,7 +3520,9 @@ describe("pump-fun", () => {
       const buyConfig = {
         user: testUser.publicKey,
         globalConfig: configPda,
-        feeRecipient: creator.publicKey,
+        creatorVault: creatorVaultPda,
+        feeStats: feeStatsPda,
+        feeVault: feeVaultPda,
         bondingCurve: bondingCurvePda,
         tokenMint: tokenMint.publicKey,
         curveTokenAccount: curveTokenAccount,
@@ -597,7 +3534,7 @@ describe("pump-fun", () => {
 
       try {
         await program.methods
-          .swap(new anchor.BN(0), 0, new anchor.BN(1))
+          .swap(new anchor.BN(0), 0, new anchor.BN(1), null)
           .accounts(buyConfig)
           .signers([testUser])
           .rpc();